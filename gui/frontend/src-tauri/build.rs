@@ -23,6 +23,30 @@ fn main() {
     // Rerun if this build script changes
     println!("cargo:rerun-if-changed=build.rs");
 
+    embed_sidecar_checksum(manifest_path);
+
     // Run Tauri's build script
     tauri_build::build()
 }
+
+// Embed the SHA-256 of the sidecar for this target so `verify_sidecar_integrity` can
+// detect a corrupted or modified binary. Empty when no sidecar has been built yet.
+fn embed_sidecar_checksum(manifest_path: &std::path::Path) {
+    use sha2::{Digest, Sha256};
+
+    let target = std::env::var("TARGET").expect("TARGET should be set by Cargo");
+    let extension = if target.contains("windows") { ".exe" } else { "" };
+    let sidecar = manifest_path
+        .join("binaries")
+        .join(format!("sts-gui-backend-{}{}", target, extension));
+    println!("cargo:rerun-if-changed={}", sidecar.display());
+
+    let checksum = match std::fs::read(&sidecar) {
+        Ok(bytes) if !bytes.is_empty() => Sha256::digest(&bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>(),
+        _ => String::new(),
+    };
+    println!("cargo:rustc-env=STS_SIDECAR_SHA256={}", checksum);
+}
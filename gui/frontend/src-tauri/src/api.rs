@@ -0,0 +1,1171 @@
+use log::{debug, info, warn};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+
+use crate::error::BackendError;
+use crate::health::backend_url;
+use crate::workspace;
+
+/// Backend endpoint that loads models/voices ahead of the first real request
+pub const WARMUP_PATH: &str = "/api/warmup";
+
+/// Warmup can legitimately take a while on a cold start
+const WARMUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Backend endpoint listing optional features (newer backends only)
+pub const CAPABILITIES_PATH: &str = "/api/capabilities";
+
+/// Single-line generation task endpoints (gui_backend/routers/generation.py)
+const GENERATE_PATH: &str = "/api/generate";
+const GENERATE_STATUS_PATH: &str = "/api/generate/status";
+
+/// Provider `benchmark` uses unless told otherwise; it runs the full pipeline without
+/// calling out to a paid API, so results reflect the backend rather than the network
+pub const BENCHMARK_DEFAULT_PROVIDER: &str = "dummy_stateless";
+
+/// Delay between task status polls while a generation runs
+const GENERATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Serves generated audio by file name (gui_backend/routers/files.py)
+const FILES_PATH: &str = "/api/files";
+
+/// Appended to the destination while `generate_to_file` is still writing it
+const PARTIAL_DOWNLOAD_SUFFIX: &str = ".part";
+
+/// Upper bound on a whole `generate_to_file` run; long jobs can be cancelled sooner
+const GENERATION_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Minimum gap between `generate_to_file` progress reports
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Audiobook generation task list and per-task cancellation (gui_backend/routers/audiobook_generation.py)
+const AUDIOBOOK_TASKS_PATH: &str = "/api/audiobook/tasks";
+
+/// How long the `cancel_all_jobs` command waits for cancelled tasks to stop; cancellation is
+/// cooperative, so a task finishes its current line first
+pub const CANCEL_CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Backend endpoint reporting its audio output settings (newer backends only)
+pub const AUDIO_CONFIG_PATH: &str = "/api/audio-config";
+
+/// Backend endpoint estimating a job's API cost and duration (newer backends only)
+pub const ESTIMATE_PATH: &str = "/api/estimate";
+
+/// Estimation parses the script, which can take a few seconds for a long PDF
+const ESTIMATE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Backend control endpoint that stops accepting work and drains in-flight requests
+/// (newer backends only); the process keeps running until it is killed
+pub const QUIESCE_PATH: &str = "/api/quiesce";
+
+/// Draining waits for in-flight requests, which may include a generation in progress
+const QUIESCE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Backend endpoint that parses a script and resolves its voices without generating audio
+/// (newer backends only)
+pub const DRY_RUN_PATH: &str = "/api/scripts/dry-run";
+
+/// Parsing and voice resolution are local to the backend but can be slow for long scripts
+const DRY_RUN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Backend endpoint reporting the settings it resolved at startup (newer backends only)
+pub const BACKEND_CONFIG_PATH: &str = "/api/debug/config";
+
+/// Setting-name fragments that mark a backend value as secret-bearing
+const SECRET_KEY_MARKERS: [&str; 6] = ["key", "token", "secret", "password", "credential", "proxy"];
+
+/// Lists the TTS providers the backend knows about (gui_backend/routers/providers.py)
+const PROVIDERS_PATH: &str = "/api/providers";
+
+/// Rebuilds the backend's provider registry in place (newer backends only)
+pub const RELOAD_PROVIDERS_PATH: &str = "/api/providers/reload";
+
+/// How long a provider's quota answer is reused; each lookup may call the provider's API
+const QUOTA_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Quota lookups call out to the provider, like credential checks
+const QUOTA_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Each provider gets its own budget so one unreachable provider can't stall the others
+const PROVIDER_CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Metadata requests should answer quickly
+const METADATA_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Creating a provider client may load its SDK, so allow for a slow first call
+const CREDENTIAL_TEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default per-call timeout for `proxy`; generous because audio generation can run for minutes.
+/// Metadata calls should pass a short timeout explicitly.
+pub const DEFAULT_PROXY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Range `set_default_request_timeout` clamps to
+const MIN_PROXY_TIMEOUT: Duration = Duration::from_secs(1);
+const MAX_PROXY_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Session-wide default for `proxy` calls that don't pass a timeout. Not persisted;
+/// starts at `DEFAULT_PROXY_TIMEOUT` on every launch.
+pub struct ProxyTimeout(AtomicU64);
+
+impl Default for ProxyTimeout {
+    fn default() -> Self {
+        ProxyTimeout(AtomicU64::new(DEFAULT_PROXY_TIMEOUT.as_millis() as u64))
+    }
+}
+
+impl ProxyTimeout {
+    pub fn get(&self) -> Duration {
+        Duration::from_millis(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Set the default (`None` restores `DEFAULT_PROXY_TIMEOUT`), clamped to 1 s - 1 h.
+    /// Returns the value now in effect.
+    pub fn set(&self, timeout_ms: Option<u64>) -> Result<Duration, BackendError> {
+        let timeout = match timeout_ms {
+            Some(0) => {
+                return Err(BackendError::InvalidArgument(
+                    "timeout_ms must be greater than 0".to_string(),
+                ))
+            }
+            Some(ms) => Duration::from_millis(ms).clamp(MIN_PROXY_TIMEOUT, MAX_PROXY_TIMEOUT),
+            None => DEFAULT_PROXY_TIMEOUT,
+        };
+        self.0.store(timeout.as_millis() as u64, Ordering::Relaxed);
+        info!("Default backend request timeout set to {:?}", timeout);
+        Ok(timeout)
+    }
+}
+
+/// Mirror of the backend's standard `ApiResponse` envelope (gui_backend/models.py)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiEnvelope {
+    pub ok: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// `ApiResponse` envelope for endpoints whose payload is read
+#[derive(Debug, Clone, Deserialize)]
+struct DataEnvelope<T> {
+    ok: bool,
+    data: Option<T>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Feature flags reported by the backend, e.g. `{"streaming": true}`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackendCapabilities {
+    /// `false` when the backend predates the capabilities endpoint; `features` is then empty
+    pub supported: bool,
+    pub features: serde_json::Map<String, serde_json::Value>,
+}
+
+impl BackendCapabilities {
+    /// Whether `feature` is reported and not switched off. Flags are usually booleans; any
+    /// other non-null value (e.g. a version or an options object) counts as supported.
+    /// Unknown features are unsupported, so older backends degrade gracefully.
+    pub fn supports(&self, feature: &str) -> bool {
+        match self.features.get(feature) {
+            None | Some(serde_json::Value::Null) => false,
+            Some(serde_json::Value::Bool(enabled)) => *enabled,
+            Some(_) => true,
+        }
+    }
+}
+
+/// Audio output settings the backend renders with; fields it doesn't report stay `None`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioOutputConfig {
+    /// Container/codec, e.g. `mp3`
+    pub format: Option<String>,
+    pub sample_rate: Option<u32>,
+    /// Bits per second
+    pub bitrate: Option<u32>,
+}
+
+/// Expected size, cost and duration of generating a script, as estimated by the backend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobEstimate {
+    /// `false` when the backend cannot estimate jobs; the other fields are then empty
+    pub supported: bool,
+    pub characters: Option<u64>,
+    pub estimated_cost: Option<f64>,
+    /// ISO 4217 code for `estimated_cost`, e.g. `USD`
+    pub currency: Option<String>,
+    pub estimated_seconds: Option<u64>,
+}
+
+/// Result of a backend dry run over a whole script
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DryRunReport {
+    /// No errors were found; warnings don't affect this
+    pub valid: bool,
+    pub issues: Vec<ScriptIssue>,
+}
+
+/// One problem found by a dry run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptIssue {
+    /// `error` or `warning`
+    pub severity: String,
+    /// 1-based line in the script, when the problem can be pinned to one
+    #[serde(default)]
+    pub line: Option<u32>,
+    pub message: String,
+}
+
+/// Outcome of `quiesce`, as reported by the backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuiesceResult {
+    /// All in-flight requests finished and buffers were flushed
+    pub drained: bool,
+    /// Requests still running when the backend answered, if it reports them
+    #[serde(default)]
+    pub pending_requests: Option<u64>,
+}
+
+/// One audiobook task as listed by the backend; other progress fields are ignored
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AudiobookTask {
+    task_id: String,
+    status: String,
+}
+
+impl AudiobookTask {
+    fn is_active(&self) -> bool {
+        matches!(self.status.as_str(), "pending" | "processing")
+    }
+}
+
+/// Outcome of `cancel_all_jobs`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CancelAllResult {
+    /// Tasks that were pending or processing and were asked to cancel
+    pub requested: usize,
+    /// Of those, how many the backend reports as cancelled
+    pub cancelled: usize,
+    /// Task ids still running (or finished some other way) when the wait ran out
+    pub unconfirmed: Vec<String>,
+}
+
+/// Whether the backend could reach one provider's API
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderConnectivity {
+    pub provider: String,
+    /// `false` when the backend has no connectivity check; `reachable` is then unknown
+    pub supported: bool,
+    pub reachable: bool,
+    /// Round trip reported by the backend, or measured here if it doesn't report one
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Usage allowance a provider reports through the backend; unknown figures stay `None`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderQuota {
+    /// What the figures count, e.g. `characters` or `credits`
+    pub unit: Option<String>,
+    pub limit: Option<f64>,
+    pub used: Option<f64>,
+    pub remaining: Option<f64>,
+    /// When the allowance resets (ISO 8601), if the provider says
+    pub resets_at: Option<String>,
+}
+
+/// A `provider_quota` answer and when it was fetched
+type CachedQuota = (Instant, Option<ProviderQuota>);
+
+/// Recent `provider_quota` answers, keyed by backend port and provider
+#[derive(Default)]
+pub struct QuotaCache(std::sync::Mutex<std::collections::HashMap<(u16, String), CachedQuota>>);
+
+impl QuotaCache {
+    /// Forget all answers, e.g. after credentials changed
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    /// Quota for `provider`, from cache if looked up within `QUOTA_CACHE_TTL`.
+    /// `None` means the provider (or the backend) doesn't report quota.
+    pub async fn get(&self, port: u16, provider: &str) -> Result<Option<ProviderQuota>, BackendError> {
+        let key = (port, provider.to_string());
+        if let Some((fetched, quota)) = self.0.lock().unwrap().get(&key) {
+            if fetched.elapsed() < QUOTA_CACHE_TTL {
+                return Ok(quota.clone());
+            }
+        }
+
+        let quota = provider_quota(port, provider).await?;
+        self.0.lock().unwrap().insert(key, (Instant::now(), quota.clone()));
+        Ok(quota)
+    }
+}
+
+/// Payload of `/api/providers/{provider}/connectivity`
+#[derive(Debug, Deserialize)]
+struct ConnectivityReport {
+    reachable: bool,
+    #[serde(default)]
+    latency_ms: Option<u64>,
+}
+
+/// Result of `test_provider_credentials`
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialTestResult {
+    pub provider: String,
+    /// `false` when the backend has no credential-test endpoint
+    pub supported: bool,
+    pub valid: bool,
+    pub message: String,
+}
+
+/// Payload of the `backend-warmed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupResult {
+    /// `false` when the backend has no warmup endpoint (older versions)
+    pub supported: bool,
+    pub elapsed_ms: u64,
+}
+
+/// Metrics from one `benchmark` run
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkResult {
+    pub provider: String,
+    pub characters: usize,
+    /// Wall-clock time from submitting the task to its completion
+    pub elapsed_ms: u64,
+    /// Length of the generated audio, when the backend reports it
+    pub audio_duration_ms: Option<u64>,
+    /// `elapsed_ms / audio_duration_ms`; below 1.0 is faster than realtime
+    pub realtime_factor: Option<f64>,
+    pub files: Vec<String>,
+}
+
+/// Payload of `generation-download-progress`, and the result of `generate_to_file`
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub bytes_written: u64,
+    /// From the backend's `Content-Length`, when it sends one
+    pub bytes_total: Option<u64>,
+    pub destination: String,
+}
+
+/// `TaskResponse` from the generation endpoint
+#[derive(Debug, Deserialize)]
+struct GenerationTask {
+    task_id: String,
+}
+
+/// The parts of `TaskStatusResponse` the generation helpers need
+#[derive(Debug, Deserialize)]
+struct GenerationStatus {
+    status: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    result: Option<GenerationOutput>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GenerationOutput {
+    #[serde(default)]
+    files: Vec<String>,
+    #[serde(default)]
+    duration_ms: Option<u64>,
+}
+
+/// Result of a proxied backend request
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyResponse {
+    pub status: u16,
+    /// Parsed JSON body, or the raw text as a JSON string if the body isn't JSON
+    pub body: serde_json::Value,
+}
+
+/// Build an HTTP client for talking to the local backend
+pub fn client(timeout: Duration) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Send a request to the backend, mapping transport failures to `BackendError`
+pub async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, BackendError> {
+    request.send().await.map_err(map_error)
+}
+
+fn map_error(e: reqwest::Error) -> BackendError {
+    if e.is_timeout() {
+        BackendError::Timeout(e.to_string())
+    } else if e.is_connect() {
+        BackendError::Unavailable(e.to_string())
+    } else {
+        BackendError::Http(e.to_string())
+    }
+}
+
+/// Provider names are interpolated into URL paths, so only allow identifier characters
+pub fn validate_provider_name(provider: &str) -> Result<(), BackendError> {
+    validate_identifier("provider name", provider)
+}
+
+/// Accept only non-empty ASCII alphanumerics, `_` and `-`; `what` names the value in errors
+pub fn validate_identifier(what: &str, value: &str) -> Result<(), BackendError> {
+    let valid = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(BackendError::InvalidArgument(format!("invalid {} {:?}", what, value)))
+    }
+}
+
+/// Ask the backend whether `provider`'s credentials are configured (its client can be created).
+/// The backend doesn't call the provider for this, so a revoked key still passes.
+pub async fn test_provider_credentials(
+    port: u16,
+    provider: &str,
+) -> Result<CredentialTestResult, BackendError> {
+    validate_provider_name(provider)?;
+
+    let path = format!("/api/providers/{}/test-credentials", provider);
+    let response = send(
+        client(CREDENTIAL_TEST_TIMEOUT)
+            .map_err(BackendError::Http)?
+            .post(backend_url(port, &path)),
+    )
+    .await?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(CredentialTestResult {
+            provider: provider.to_string(),
+            supported: false,
+            valid: false,
+            message: "This backend version cannot test provider credentials".to_string(),
+        });
+    }
+
+    let envelope: ApiEnvelope = response
+        .json()
+        .await
+        .map_err(|e| BackendError::Http(format!("unexpected credential test response: {}", e)))?;
+
+    let message = match (envelope.ok, envelope.error) {
+        (_, Some(error)) => error,
+        (true, None) => "Credentials are configured".to_string(),
+        (false, None) => "Credentials were rejected".to_string(),
+    };
+    Ok(CredentialTestResult {
+        provider: provider.to_string(),
+        supported: true,
+        valid: envelope.ok,
+        message,
+    })
+}
+
+/// Check every real (non-dummy) provider concurrently, each with its own timeout.
+/// Only listing the providers can fail; per-provider problems are reported in the results.
+pub async fn check_provider_connectivity(port: u16) -> Result<Vec<ProviderConnectivity>, BackendError> {
+    let checks: Vec<_> = providers(port)
+        .await?
+        .into_iter()
+        .filter(|provider| !provider.starts_with("dummy"))
+        .map(|provider| tauri::async_runtime::spawn(provider_connectivity(port, provider)))
+        .collect();
+
+    let mut results = Vec::with_capacity(checks.len());
+    for check in checks {
+        results.push(check.await.map_err(|e| BackendError::Http(e.to_string()))?);
+    }
+    Ok(results)
+}
+
+/// Names of the TTS providers the backend has registered
+pub async fn providers(port: u16) -> Result<Vec<String>, BackendError> {
+    send(
+        client(METADATA_TIMEOUT)
+            .map_err(BackendError::Http)?
+            .get(backend_url(port, PROVIDERS_PATH)),
+    )
+    .await?
+    .json()
+    .await
+    .map_err(|e| BackendError::Http(format!("unexpected provider list: {}", e)))
+}
+
+/// Ask the backend to rebuild its provider registry (e.g. after new credentials).
+/// Fails with `Unsupported` on backends that can only pick providers up at startup.
+pub async fn reload_providers(port: u16) -> Result<(), BackendError> {
+    let response = send(
+        client(METADATA_TIMEOUT)
+            .map_err(BackendError::Http)?
+            .post(backend_url(port, RELOAD_PROVIDERS_PATH)),
+    )
+    .await?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+        return Err(BackendError::Unsupported(
+            "this backend version cannot reload its providers without restarting".to_string(),
+        ));
+    }
+    if !status.is_success() {
+        let detail = response.text().await.unwrap_or_default();
+        return Err(BackendError::Http(format!(
+            "provider reload returned HTTP {}: {}",
+            status, detail
+        )));
+    }
+    Ok(())
+}
+
+/// Ask the backend for `provider`'s remaining quota. Backends without the endpoint, and
+/// providers that don't report quota, both give `None`.
+pub async fn provider_quota(port: u16, provider: &str) -> Result<Option<ProviderQuota>, BackendError> {
+    validate_provider_name(provider)?;
+    let path = format!("{}/{}/quota", PROVIDERS_PATH, provider);
+    let response = send(
+        client(QUOTA_TIMEOUT)
+            .map_err(BackendError::Http)?
+            .get(backend_url(port, &path)),
+    )
+    .await?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(BackendError::Http(format!("{} returned HTTP {}", path, response.status())));
+    }
+
+    let envelope: DataEnvelope<ProviderQuota> = response
+        .json()
+        .await
+        .map_err(|e| BackendError::Http(format!("unexpected response from {}: {}", path, e)))?;
+    if !envelope.ok {
+        return Err(BackendError::Http(
+            envelope
+                .error
+                .unwrap_or_else(|| format!("{} reported a failure", path)),
+        ));
+    }
+    Ok(envelope.data)
+}
+
+async fn provider_connectivity(port: u16, provider: String) -> ProviderConnectivity {
+    let mut result = ProviderConnectivity {
+        provider,
+        supported: true,
+        reachable: false,
+        latency_ms: None,
+        error: None,
+    };
+    if let Err(e) = validate_provider_name(&result.provider) {
+        result.error = Some(e.to_string());
+        return result;
+    }
+
+    let path = format!("/api/providers/{}/connectivity", result.provider);
+    let started = Instant::now();
+    let response = match client(PROVIDER_CONNECTIVITY_TIMEOUT) {
+        Ok(client) => send(client.get(backend_url(port, &path))).await,
+        Err(e) => Err(BackendError::Http(e)),
+    };
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        result.supported = false;
+        result.error = Some("This backend version cannot check provider connectivity".to_string());
+        return result;
+    }
+
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    match response.json::<DataEnvelope<ConnectivityReport>>().await {
+        Ok(DataEnvelope {
+            ok: true,
+            data: Some(report),
+            ..
+        }) => {
+            result.reachable = report.reachable;
+            result.latency_ms = Some(report.latency_ms.unwrap_or(elapsed_ms));
+        }
+        Ok(envelope) => {
+            result.error = Some(
+                envelope
+                    .error
+                    .unwrap_or_else(|| "connectivity check returned no data".to_string()),
+            );
+        }
+        Err(e) => result.error = Some(format!("unexpected connectivity response: {}", e)),
+    }
+    result
+}
+
+/// Forward an arbitrary request to the backend.
+///
+/// `timeout` covers the whole exchange, including reading the body. When it fires,
+/// reqwest drops the connection (cancelling the request) and this returns
+/// `BackendError::Timeout`, distinct from the backend being unreachable.
+pub async fn proxy(
+    port: u16,
+    method: &str,
+    path: &str,
+    body: Option<serde_json::Value>,
+    timeout: Duration,
+) -> Result<ProxyResponse, BackendError> {
+    // Only relative paths on the local backend; never let the caller pick the host
+    if !path.starts_with('/') || path.starts_with("//") {
+        return Err(BackendError::InvalidArgument(format!(
+            "path {:?} must be an absolute path on the backend",
+            path
+        )));
+    }
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| BackendError::InvalidArgument(format!("invalid HTTP method {:?}", method)))?;
+
+    let mut request = client(timeout)
+        .map_err(BackendError::Http)?
+        .request(method, backend_url(port, path));
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = send(request).await?;
+    let status = response.status().as_u16();
+    let text = response.text().await.map_err(map_error)?;
+
+    let body = if text.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text))
+    };
+    Ok(ProxyResponse { status, body })
+}
+
+/// GET an `ApiResponse`-wrapped payload, returning `None` if the endpoint doesn't exist
+pub async fn get_data<T: DeserializeOwned>(port: u16, path: &str) -> Result<Option<T>, BackendError> {
+    let response = send(
+        client(METADATA_TIMEOUT)
+            .map_err(BackendError::Http)?
+            .get(backend_url(port, path)),
+    )
+    .await?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(BackendError::Http(format!("{} returned HTTP {}", path, response.status())));
+    }
+
+    let envelope: DataEnvelope<T> = response
+        .json()
+        .await
+        .map_err(|e| BackendError::Http(format!("unexpected response from {}: {}", path, e)))?;
+    match (envelope.ok, envelope.data) {
+        (true, Some(data)) => Ok(Some(data)),
+        _ => Err(BackendError::Http(
+            envelope
+                .error
+                .unwrap_or_else(|| format!("{} returned no data", path)),
+        )),
+    }
+}
+
+/// Fetch the backend's feature flags; older backends without the endpoint get an empty set
+pub async fn capabilities(port: u16) -> Result<BackendCapabilities, BackendError> {
+    match get_data(port, CAPABILITIES_PATH).await? {
+        Some(features) => Ok(BackendCapabilities {
+            supported: true,
+            features,
+        }),
+        None => {
+            debug!("Backend has no capabilities endpoint; assuming no optional features");
+            Ok(BackendCapabilities::default())
+        }
+    }
+}
+
+/// Generate `text` once with `provider` and time it end to end.
+/// `timeout` bounds the whole run, including queueing behind other tasks.
+pub async fn benchmark(
+    port: u16,
+    provider: &str,
+    config: serde_json::Value,
+    text: &str,
+    timeout: Duration,
+) -> Result<BenchmarkResult, BackendError> {
+    validate_provider_name(provider)?;
+    if text.trim().is_empty() {
+        return Err(BackendError::InvalidArgument("benchmark sample text is empty".to_string()));
+    }
+
+    let started = Instant::now();
+    let output = generate(port, provider, config, text, timeout).await?;
+
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    let realtime_factor = output
+        .duration_ms
+        .filter(|&duration| duration > 0)
+        .map(|duration| elapsed_ms as f64 / duration as f64);
+    info!(
+        "Benchmark with {}: {} chars in {} ms (realtime factor {:?})",
+        provider,
+        text.chars().count(),
+        elapsed_ms,
+        realtime_factor
+    );
+    Ok(BenchmarkResult {
+        provider: provider.to_string(),
+        characters: text.chars().count(),
+        elapsed_ms,
+        audio_duration_ms: output.duration_ms,
+        realtime_factor,
+        files: output.files,
+    })
+}
+
+/// Submit a single-variant generation and poll until it finishes or `timeout` elapses
+async fn generate(
+    port: u16,
+    provider: &str,
+    config: serde_json::Value,
+    text: &str,
+    timeout: Duration,
+) -> Result<GenerationOutput, BackendError> {
+    let client = client(METADATA_TIMEOUT).map_err(BackendError::Http)?;
+    let started = Instant::now();
+    let response = send(client.post(backend_url(port, GENERATE_PATH)).json(&serde_json::json!({
+        "provider": provider,
+        "config": config,
+        "text": text,
+        "variants": 1,
+    })))
+    .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(BackendError::Http(format!(
+            "generation request returned HTTP {}: {}",
+            status, detail
+        )));
+    }
+    let task: GenerationTask = response
+        .json()
+        .await
+        .map_err(|e| BackendError::Http(format!("unexpected generation response: {}", e)))?;
+
+    let status_url = backend_url(port, &format!("{}/{}", GENERATE_STATUS_PATH, task.task_id));
+    loop {
+        if started.elapsed() >= timeout {
+            return Err(BackendError::Timeout(format!(
+                "generation task {} did not finish within {:?}",
+                task.task_id, timeout
+            )));
+        }
+        tokio::time::sleep(GENERATION_POLL_INTERVAL).await;
+
+        let status: GenerationStatus = send(client.get(&status_url))
+            .await?
+            .json()
+            .await
+            .map_err(|e| BackendError::Http(format!("unexpected task status response: {}", e)))?;
+        match status.status.as_str() {
+            "completed" => return Ok(status.result.unwrap_or_default()),
+            "failed" | "cancelled" => {
+                return Err(BackendError::Http(status.error.unwrap_or(status.message)));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Generate `text` with `provider` and stream the audio into `destination`.
+///
+/// Bytes go to `<destination>.part` first and are renamed into place once complete, so an
+/// existing file is only replaced by a finished download. `report` receives throttled
+/// progress while streaming. If `cancel` fires or anything fails, the partial file is removed.
+pub async fn generate_to_file(
+    port: u16,
+    provider: &str,
+    config: serde_json::Value,
+    text: &str,
+    destination: &Path,
+    cancel: tokio::sync::oneshot::Receiver<()>,
+    report: impl FnMut(&DownloadProgress),
+) -> Result<DownloadProgress, BackendError> {
+    validate_provider_name(provider)?;
+    if text.trim().is_empty() {
+        return Err(BackendError::InvalidArgument("generation text is empty".to_string()));
+    }
+
+    let mut partial = destination.as_os_str().to_owned();
+    partial.push(PARTIAL_DOWNLOAD_SUFFIX);
+    let partial = PathBuf::from(partial);
+
+    let result = tokio::select! {
+        result = generate_and_stream(port, provider, config, text, destination, &partial, report) => result,
+        _ = cancel => Err(BackendError::Cancelled(format!("download to {:?} was cancelled", destination))),
+    };
+
+    match result {
+        Ok(progress) => {
+            tokio::fs::rename(&partial, destination)
+                .await
+                .map_err(|e| workspace::io_error("move download to", destination, e))?;
+            info!("Saved generated audio to {:?} ({} bytes)", destination, progress.bytes_written);
+            Ok(progress)
+        }
+        Err(e) => {
+            if let Err(remove_error) = tokio::fs::remove_file(&partial).await {
+                if remove_error.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to remove partial download {:?}: {}", partial, remove_error);
+                }
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn generate_and_stream(
+    port: u16,
+    provider: &str,
+    config: serde_json::Value,
+    text: &str,
+    destination: &Path,
+    partial: &Path,
+    mut report: impl FnMut(&DownloadProgress),
+) -> Result<DownloadProgress, BackendError> {
+    let started = Instant::now();
+    let output = generate(port, provider, config, text, GENERATION_DOWNLOAD_TIMEOUT).await?;
+    let filename = output
+        .files
+        .into_iter()
+        .next()
+        .ok_or_else(|| BackendError::Http("generation finished without producing a file".to_string()))?;
+    // File names come from the backend but end up in a URL path
+    if filename.contains(['/', '\\']) || filename.contains("..") {
+        return Err(BackendError::Http(format!("unexpected generated file name {:?}", filename)));
+    }
+
+    let remaining = GENERATION_DOWNLOAD_TIMEOUT.saturating_sub(started.elapsed());
+    let mut response = send(client(remaining).map_err(BackendError::Http)?.get(backend_url(
+        port,
+        &format!("{}/{}", FILES_PATH, filename),
+    )))
+    .await?;
+    if !response.status().is_success() {
+        return Err(BackendError::Http(format!(
+            "download of {} returned HTTP {}",
+            filename,
+            response.status()
+        )));
+    }
+
+    let mut progress = DownloadProgress {
+        bytes_written: 0,
+        bytes_total: response.content_length(),
+        destination: destination.display().to_string(),
+    };
+    let mut file = tokio::fs::File::create(partial)
+        .await
+        .map_err(|e| workspace::io_error("create", partial, e))?;
+    let mut last_report = Instant::now();
+    report(&progress);
+
+    while let Some(chunk) = response.chunk().await.map_err(map_error)? {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| workspace::io_error("write", partial, e))?;
+        progress.bytes_written += chunk.len() as u64;
+        if last_report.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL {
+            report(&progress);
+            last_report = Instant::now();
+        }
+    }
+    if let Some(total) = progress.bytes_total.filter(|&total| total != progress.bytes_written) {
+        return Err(BackendError::Http(format!(
+            "download of {} ended after {} of {} bytes",
+            filename, progress.bytes_written, total
+        )));
+    }
+
+    file.sync_all()
+        .await
+        .map_err(|e| workspace::io_error("flush", partial, e))?;
+    report(&progress);
+    Ok(progress)
+}
+
+/// Ask the backend to estimate the job for `script_path`.
+/// Backends without the endpoint get `supported: false` rather than an error.
+pub async fn estimate_job(port: u16, script_path: &str) -> Result<JobEstimate, BackendError> {
+    let response = send(
+        client(ESTIMATE_TIMEOUT)
+            .map_err(BackendError::Http)?
+            .post(backend_url(port, ESTIMATE_PATH))
+            .json(&serde_json::json!({ "script_path": script_path })),
+    )
+    .await?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+        debug!("Backend has no estimation endpoint (HTTP {})", status);
+        return Ok(JobEstimate::default());
+    }
+
+    let envelope: DataEnvelope<JobEstimate> = response
+        .json()
+        .await
+        .map_err(|e| BackendError::Http(format!("unexpected estimate response: {}", e)))?;
+    match (envelope.ok, envelope.data) {
+        (true, Some(estimate)) => Ok(JobEstimate {
+            supported: true,
+            ..estimate
+        }),
+        _ => Err(BackendError::Http(
+            envelope
+                .error
+                .unwrap_or_else(|| format!("estimate returned HTTP {}", status)),
+        )),
+    }
+}
+
+/// Have the backend parse `script_path` and check every voice resolves, without
+/// generating anything. Backends without the endpoint get `BackendError::Unsupported`.
+pub async fn dry_run_script(port: u16, script_path: &str) -> Result<DryRunReport, BackendError> {
+    let response = send(
+        client(DRY_RUN_TIMEOUT)
+            .map_err(BackendError::Http)?
+            .post(backend_url(port, DRY_RUN_PATH))
+            .json(&serde_json::json!({ "script_path": script_path })),
+    )
+    .await?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+        return Err(BackendError::Unsupported(
+            "this backend version cannot validate a script without generating it".to_string(),
+        ));
+    }
+
+    let envelope: DataEnvelope<DryRunReport> = response
+        .json()
+        .await
+        .map_err(|e| BackendError::Http(format!("unexpected dry run response: {}", e)))?;
+    match (envelope.ok, envelope.data) {
+        (true, Some(report)) => Ok(report),
+        _ => Err(BackendError::Http(
+            envelope
+                .error
+                .unwrap_or_else(|| format!("dry run returned HTTP {}", status)),
+        )),
+    }
+}
+
+/// Ask the backend to stop serving and drain; it answers once drained (or gives up).
+/// Backends without the control endpoint get `BackendError::Unsupported`.
+pub async fn quiesce(port: u16) -> Result<QuiesceResult, BackendError> {
+    let response = send(
+        client(QUIESCE_TIMEOUT)
+            .map_err(BackendError::Http)?
+            .post(backend_url(port, QUIESCE_PATH)),
+    )
+    .await?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+        return Err(BackendError::Unsupported(
+            "this backend version cannot shut down its server separately from the process".to_string(),
+        ));
+    }
+
+    let envelope: DataEnvelope<QuiesceResult> = response
+        .json()
+        .await
+        .map_err(|e| BackendError::Http(format!("unexpected quiesce response: {}", e)))?;
+    match (envelope.ok, envelope.data) {
+        (true, Some(result)) => Ok(result),
+        _ => Err(BackendError::Http(
+            envelope
+                .error
+                .unwrap_or_else(|| format!("quiesce returned HTTP {}", status)),
+        )),
+    }
+}
+
+/// Cancel every pending or processing audiobook task, then wait up to `confirm_within`
+/// for the backend to report them cancelled (`Duration::ZERO` checks once and returns).
+/// Backends without the task list get `BackendError::Unsupported`.
+pub async fn cancel_all_jobs(port: u16, confirm_within: Duration) -> Result<CancelAllResult, BackendError> {
+    let client = client(METADATA_TIMEOUT).map_err(BackendError::Http)?;
+    let active: Vec<String> = audiobook_tasks(&client, port)
+        .await?
+        .into_iter()
+        .filter(AudiobookTask::is_active)
+        .map(|task| task.task_id)
+        .collect();
+    if active.is_empty() {
+        return Ok(CancelAllResult::default());
+    }
+
+    let mut requested = Vec::with_capacity(active.len());
+    for task_id in active {
+        validate_identifier("task id", &task_id)?;
+        let path = format!("/api/audiobook/{}/cancel", task_id);
+        let response = send(client.post(backend_url(port, &path))).await?;
+        match response.status() {
+            status if status.is_success() => requested.push(task_id),
+            // Finished (409) or was dropped (404) between listing and cancelling
+            reqwest::StatusCode::CONFLICT | reqwest::StatusCode::NOT_FOUND => {
+                debug!("Task {} finished before it could be cancelled", task_id)
+            }
+            status => warn!("Cancelling task {} returned HTTP {}", task_id, status),
+        }
+    }
+    info!("Requested cancellation of {} backend task(s)", requested.len());
+
+    let deadline = Instant::now() + confirm_within;
+    loop {
+        let tasks = audiobook_tasks(&client, port).await?;
+        let status_of = |task_id: &str| {
+            tasks
+                .iter()
+                .find(|task| task.task_id == task_id)
+                .map(|task| task.status.as_str())
+        };
+        let cancelled = requested
+            .iter()
+            .filter(|task_id| status_of(task_id) == Some("cancelled"))
+            .count();
+        let still_active = requested
+            .iter()
+            .any(|task_id| matches!(status_of(task_id), Some("pending" | "processing")));
+
+        if !still_active || Instant::now() >= deadline {
+            let unconfirmed: Vec<String> = requested
+                .iter()
+                .filter(|task_id| status_of(task_id) != Some("cancelled"))
+                .cloned()
+                .collect();
+            if !unconfirmed.is_empty() {
+                warn!("Backend tasks not confirmed cancelled: {:?}", unconfirmed);
+            }
+            return Ok(CancelAllResult {
+                requested: requested.len(),
+                cancelled,
+                unconfirmed,
+            });
+        }
+        tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
+    }
+}
+
+/// The backend's audiobook tasks; the list is returned bare, not in an `ApiResponse`
+async fn audiobook_tasks(client: &reqwest::Client, port: u16) -> Result<Vec<AudiobookTask>, BackendError> {
+    let response = send(client.get(backend_url(port, AUDIOBOOK_TASKS_PATH))).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(BackendError::Unsupported(
+            "this backend version cannot list or cancel its jobs".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(BackendError::Http(format!(
+            "{} returned HTTP {}",
+            AUDIOBOOK_TASKS_PATH,
+            response.status()
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| BackendError::Http(format!("unexpected response from {}: {}", AUDIOBOOK_TASKS_PATH, e)))
+}
+
+/// Fetch the backend's audio output settings
+pub async fn audio_config(port: u16) -> Result<AudioOutputConfig, BackendError> {
+    get_data(port, AUDIO_CONFIG_PATH).await?.ok_or_else(|| {
+        BackendError::Unavailable("this backend version does not report its audio output settings".to_string())
+    })
+}
+
+/// Settings the backend actually resolved, with secret-bearing values replaced by
+/// `"<redacted>"`. Fails with `Unsupported` on backends without the endpoint.
+pub async fn backend_config(port: u16) -> Result<serde_json::Value, BackendError> {
+    let mut config: serde_json::Value = get_data(port, BACKEND_CONFIG_PATH).await?.ok_or_else(|| {
+        BackendError::Unsupported("this backend version does not report its configuration".to_string())
+    })?;
+    redact_secrets(&mut config);
+    Ok(config)
+}
+
+/// Replace the value of every secret-looking key, at any depth
+fn redact_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if !value.is_null() && SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker)) {
+                    *value = serde_json::Value::String("<redacted>".to_string());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+/// Ask the backend to preload models/voices.
+/// A missing endpoint is not an error; it is reported as `supported: false`.
+pub async fn prewarm(port: u16) -> Result<WarmupResult, String> {
+    let started = Instant::now();
+    let response = client(WARMUP_TIMEOUT)?
+        .post(backend_url(port, WARMUP_PATH))
+        .send()
+        .await
+        .map_err(|e| format!("Warmup request failed: {}", e))?;
+
+    let status = response.status();
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+        debug!("Backend has no warmup endpoint (HTTP {}), skipping prewarm", status);
+        return Ok(WarmupResult {
+            supported: false,
+            elapsed_ms,
+        });
+    }
+    if !status.is_success() {
+        return Err(format!("Warmup returned HTTP {}", status));
+    }
+
+    info!("Backend prewarmed in {} ms", elapsed_ms);
+    Ok(WarmupResult {
+        supported: true,
+        elapsed_ms,
+    })
+}
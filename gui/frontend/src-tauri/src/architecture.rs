@@ -0,0 +1,197 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::BackendError;
+
+/// Bytes of the executable read to identify it; enough for every header field used below
+/// except the PE header, which is located through `e_lfanew`
+const HEADER_LEN: usize = 4096;
+
+/// Outcome of `check`
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchitectureReport {
+    pub path: String,
+    /// Architectures the sidecar was built for; more than one for a universal binary
+    pub sidecar_archs: Vec<&'static str>,
+    /// What this app was built for
+    pub app_arch: &'static str,
+    /// What the machine is, which differs from `app_arch` when the app itself is emulated
+    pub host_arch: &'static str,
+    /// Whether the sidecar can start at all on this machine
+    pub compatible: bool,
+    /// Whether it will only run through emulation (Rosetta, Windows on Arm), which is slower
+    pub emulated: bool,
+    pub detail: String,
+}
+
+/// Identify the sidecar's CPU architecture and compare it with the host's
+pub fn check(sidecar: &Path) -> Result<ArchitectureReport, BackendError> {
+    let sidecar_archs = binary_archs(sidecar)?;
+    let app_arch = std::env::consts::ARCH;
+    let host_arch = host_arch();
+
+    let native = sidecar_archs.contains(&host_arch);
+    let emulated = !native && sidecar_archs.iter().any(|arch| can_emulate(host_arch, arch));
+    let compatible = native || emulated;
+
+    let built_for = sidecar_archs.join(", ");
+    let detail = if native {
+        format!("the backend runs natively on {}", host_arch)
+    } else if emulated {
+        format!(
+            "the backend is built for {} and will run under emulation on this {} machine, \
+             which is slower{}; download the {} build of the app for full speed",
+            built_for,
+            host_arch,
+            if cfg!(target_os = "macos") { " and needs Rosetta" } else { "" },
+            host_arch
+        )
+    } else {
+        format!(
+            "the backend is built for {} and cannot run on this {} machine; \
+             download the {} build of the app",
+            built_for, host_arch, host_arch
+        )
+    };
+
+    if compatible && !emulated {
+        info!("Sidecar {:?} architecture {} matches the host", sidecar, built_for);
+    } else {
+        warn!("Sidecar {:?}: {}", sidecar, detail);
+    }
+    Ok(ArchitectureReport {
+        path: sidecar.display().to_string(),
+        sidecar_archs,
+        app_arch,
+        host_arch,
+        compatible,
+        emulated,
+        detail,
+    })
+}
+
+/// If `binary` cannot run on this machine, explain why a spawn failed;
+/// otherwise `None` so the original error is shown
+pub fn explain_spawn_failure(binary: &Path, error: &str) -> Option<String> {
+    let report = check(binary).ok()?;
+    (!report.compatible).then(|| format!("The bundled backend cannot start ({}): {}", error, report.detail))
+}
+
+/// The machine's architecture, seeing through Rosetta and Windows x64 emulation
+fn host_arch() -> &'static str {
+    let app_arch = std::env::consts::ARCH;
+    if cfg!(target_os = "macos") && app_arch == "x86_64" {
+        let arm64 = Command::new("sysctl")
+            .args(["-n", "hw.optional.arm64"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1");
+        if arm64 {
+            return "aarch64";
+        }
+    }
+    if cfg!(windows) {
+        // Set for emulated processes; otherwise PROCESSOR_ARCHITECTURE is the native one
+        let native = std::env::var("PROCESSOR_ARCHITEW6432").or_else(|_| std::env::var("PROCESSOR_ARCHITECTURE"));
+        match native.as_deref() {
+            Ok("ARM64") => return "aarch64",
+            Ok("AMD64") => return "x86_64",
+            _ => {}
+        }
+    }
+    app_arch
+}
+
+/// Whether `host` runs `binary_arch` code through the OS's built-in emulation
+fn can_emulate(host: &str, binary_arch: &str) -> bool {
+    host == "aarch64" && binary_arch == "x86_64" && (cfg!(target_os = "macos") || cfg!(windows))
+}
+
+/// Architectures named in the Mach-O, ELF or PE header of `path`
+fn binary_archs(path: &Path) -> Result<Vec<&'static str>, BackendError> {
+    let io_error = |e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::NotFound => BackendError::NotFound(format!("{:?} does not exist", path)),
+        _ => BackendError::Io(format!("failed to read {:?}: {}", path, e)),
+    };
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    std::fs::File::open(path)
+        .map_err(io_error)?
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut header)
+        .map_err(io_error)?;
+
+    let archs = match header.get(..4) {
+        Some([0xcf, 0xfa, 0xed, 0xfe]) | Some([0xce, 0xfa, 0xed, 0xfe]) => {
+            read_u32_le(&header, 4).and_then(mach_cpu_arch).into_iter().collect()
+        }
+        // Universal binary: big-endian count, then 20-byte entries starting with the CPU type
+        Some([0xca, 0xfe, 0xba, 0xbe]) => {
+            let count = read_u32_be(&header, 4).unwrap_or(0) as usize;
+            (0..count.min(16))
+                .filter_map(|i| read_u32_be(&header, 8 + i * 20).and_then(mach_cpu_arch))
+                .collect()
+        }
+        Some([0x7f, b'E', b'L', b'F']) => {
+            read_u16_le(&header, 18).and_then(elf_machine_arch).into_iter().collect()
+        }
+        Some([b'M', b'Z', ..]) => {
+            let pe_offset = read_u32_le(&header, 0x3c).unwrap_or(0) as usize;
+            if header.get(pe_offset..pe_offset + 4) == Some(b"PE\0\0") {
+                read_u16_le(&header, pe_offset + 4).and_then(pe_machine_arch).into_iter().collect()
+            } else {
+                Vec::new()
+            }
+        }
+        _ => Vec::new(),
+    };
+
+    if archs.is_empty() {
+        return Err(BackendError::Unsupported(format!(
+            "could not identify the architecture of {:?}",
+            path
+        )));
+    }
+    Ok(archs)
+}
+
+fn mach_cpu_arch(cpu_type: u32) -> Option<&'static str> {
+    match cpu_type {
+        0x0100_0007 => Some("x86_64"),
+        0x0100_000c => Some("aarch64"),
+        7 => Some("x86"),
+        _ => None,
+    }
+}
+
+fn elf_machine_arch(machine: u16) -> Option<&'static str> {
+    match machine {
+        0x3e => Some("x86_64"),
+        0xb7 => Some("aarch64"),
+        0x03 => Some("x86"),
+        _ => None,
+    }
+}
+
+fn pe_machine_arch(machine: u16) -> Option<&'static str> {
+    match machine {
+        0x8664 => Some("x86_64"),
+        0xaa64 => Some("aarch64"),
+        0x014c => Some("x86"),
+        _ => None,
+    }
+}
+
+fn read_u16_le(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32_le(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn read_u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
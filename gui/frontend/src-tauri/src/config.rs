@@ -0,0 +1,275 @@
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::error::BackendError;
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Suffix of the scratch file `save` writes before renaming it over the config
+const TEMP_FILE_SUFFIX: &str = ".tmp";
+
+/// Environment variable that turns backend autostart off (`0`/`false`) or on (`1`/`true`)
+pub const AUTOSTART_ENV_VAR: &str = "STS_AUTOSTART";
+
+/// Persistent GUI settings stored as JSON in the AppLocalData directory.
+///
+/// Every field is optional so that older or hand-edited config files keep loading;
+/// missing values fall back to the built-in defaults at the point of use.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Backend port override (validated by `port::resolve_port`, so kept wider than u16 here)
+    pub port: Option<u32>,
+    /// Readiness/health endpoint path, e.g. `/health` or `/ready`
+    pub health_path: Option<String>,
+    /// Keep the main window hidden until the backend is ready (default: show immediately)
+    pub show_window_on_ready: Option<bool>,
+    /// Force `"dev"` or `"prod"` backend instead of detecting it from the sidecar
+    pub force_mode: Option<String>,
+    /// Rust-side log verbosity (`error`..`trace`, or `off`), set via `set_log_verbosity`
+    pub log_level: Option<String>,
+    /// `console`, `file` or `both` (default), set via `set_log_sink`
+    pub log_sink: Option<String>,
+    /// Rotated app log files to keep, set via `set_log_retention` (default 5)
+    pub log_retention: Option<u32>,
+    /// Workspace location chosen via `migrate_workspace`, replacing the per-mode default
+    pub workspace_dir: Option<String>,
+    /// Backend worker count passed as `--workers`; unset leaves it to the backend
+    pub workers: Option<u32>,
+    /// `cpu` or `gpu`, passed as `--device`; unset (`auto`) leaves it to the backend
+    pub device: Option<String>,
+    /// Where finished audiobooks are written, passed as `--output-dir`; unset keeps them in the workspace
+    pub output_dir: Option<String>,
+    /// Script file extensions accepted by `validate_script_path` (default: pdf, txt)
+    pub script_extensions: Option<Vec<String>>,
+    /// Readiness probe attempts (`STS_HEALTH_RETRIES` overrides)
+    pub health_retries: Option<u32>,
+    /// Delay between readiness probes in ms (`STS_HEALTH_INTERVAL_MS` overrides)
+    pub health_interval_ms: Option<u64>,
+    /// Watchdog probe interval in ms (`STS_WATCHDOG_INTERVAL_MS` overrides)
+    pub watchdog_interval_ms: Option<u64>,
+    /// Consecutive failed probes before `backend-unresponsive` (`STS_WATCHDOG_THRESHOLD` overrides)
+    pub watchdog_failure_threshold: Option<u32>,
+    /// Active backend profile, passed as `--profile`; set via `switch_profile`
+    pub profile: Option<String>,
+    /// External tools `check_external_tools` looks for (default: `uv` in dev mode, none bundled)
+    pub required_tools: Option<Vec<String>>,
+    /// Start the backend when the app launches (default: true). Disable to attach an
+    /// external backend or start it manually from the UI.
+    pub autostart: Option<bool>,
+    /// Copy backend stdout/stderr into the app log (default: debug builds only);
+    /// set via `set_output_capture` to collect a reproduction from a release build
+    pub capture_output: Option<bool>,
+    /// Whether the sidecar exits when its stdin closes because the app died (default: true);
+    /// set via `set_parent_death_shutdown`
+    pub parent_death_shutdown: Option<bool>,
+    /// Proxy for the backend's outbound provider calls, set via `set_backend_proxy`
+    pub proxy_url: Option<String>,
+    /// Named workspace presets (name -> directory) for `switch_workspace`
+    pub workspaces: Option<BTreeMap<String, String>>,
+    /// Restart the backend after the machine wakes (default: false); set via `set_power_handling`
+    pub restart_on_resume: Option<bool>,
+    /// Backend memory cap in MB (default: unlimited), set via `set_backend_resource_limits`
+    pub backend_max_memory_mb: Option<u64>,
+    /// Backend CPU time cap in seconds (default: unlimited), set via `set_backend_resource_limits`
+    pub backend_max_cpu_seconds: Option<u64>,
+}
+
+/// Fully resolved settings after env vars, config file, and defaults are merged.
+/// Intended for support/diagnostics; any secret-bearing value must be redacted here.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveConfig {
+    pub mode: &'static str,
+    pub mode_source: &'static str,
+    pub host: &'static str,
+    pub port: u16,
+    pub port_source: &'static str,
+    pub health_path: String,
+    pub ready_timeout_ms: u64,
+    pub health_retries: u32,
+    pub health_interval_ms: u64,
+    pub workspace_dir: String,
+    pub config_file: Option<String>,
+    pub show_window_on_ready: bool,
+    pub autostart: bool,
+    pub profile: Option<String>,
+    pub log_level: String,
+}
+
+/// Managed state holding the config loaded at startup
+pub struct ConfigState(pub Mutex<AppConfig>);
+
+/// Location of the config file: `<AppLocalData>/config.json`
+pub fn config_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    use tauri::path::BaseDirectory;
+
+    app_handle
+        .path()
+        .resolve(CONFIG_FILE_NAME, BaseDirectory::AppLocalData)
+        .map_err(|e| format!("Failed to resolve config path: {}", e))
+}
+
+/// A config file that could not be parsed and was set aside; payload of `config-repaired`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigRepair {
+    pub path: String,
+    /// Where the corrupt file was moved, if moving it worked
+    pub backup_path: Option<String>,
+    pub error: String,
+}
+
+/// Load the config file, falling back to defaults if it is missing or unreadable.
+/// A broken config should never prevent the app from starting: a file that doesn't parse
+/// (e.g. cut short by a power loss) is moved aside and reported in the returned repair.
+pub fn load(app_handle: &AppHandle) -> (AppConfig, Option<ConfigRepair>) {
+    let path = match config_path(app_handle) {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("{}; using default config", e);
+            return (AppConfig::default(), None);
+        }
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(config) => (config, None),
+            Err(e) => {
+                warn!("Failed to parse config {:?}: {}; using default config", path, e);
+                (AppConfig::default(), Some(set_aside(&path, e.to_string())))
+            }
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!("No config file at {:?}; using default config", path);
+            (AppConfig::default(), None)
+        }
+        Err(e) => {
+            warn!("Failed to read config {:?}: {}; using default config", path, e);
+            (AppConfig::default(), None)
+        }
+    }
+}
+
+/// Check the config file on disk and, if it doesn't parse, back it up and write the loaded
+/// config in its place. Returns the repair made, if any.
+pub fn repair(app_handle: &AppHandle) -> Result<Option<ConfigRepair>, String> {
+    // Held throughout so an `update` can't write in between the check and the rewrite
+    let state: tauri::State<ConfigState> = app_handle.state();
+    let config = state.0.lock().unwrap();
+
+    let path = config_path(app_handle)?;
+    let error = match std::fs::read_to_string(&path) {
+        Ok(contents) => match serde_json::from_str::<AppConfig>(&contents) {
+            Ok(_) => return Ok(None),
+            Err(e) => e.to_string(),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => format!("unreadable: {}", e),
+    };
+
+    let repair = set_aside(&path, error);
+    save(app_handle, &config)?;
+    Ok(Some(repair))
+}
+
+/// Move a corrupt config to `config.json.corrupt-<unix seconds>` so it can be inspected
+fn set_aside(path: &Path, error: String) -> ConfigRepair {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".corrupt-{}", stamp));
+    let backup = PathBuf::from(backup);
+
+    let backup_path = match std::fs::rename(path, &backup) {
+        Ok(()) => {
+            warn!("Moved corrupt config {:?} to {:?}", path, backup);
+            Some(backup.to_string_lossy().into_owned())
+        }
+        Err(e) => {
+            warn!("Failed to back up corrupt config {:?}: {}", path, e);
+            None
+        }
+    };
+    ConfigRepair {
+        path: path.to_string_lossy().into_owned(),
+        backup_path,
+        error,
+    }
+}
+
+/// Write `config` to disk, creating the AppLocalData directory if needed.
+/// Written to a temporary file first and renamed into place, so an interrupted save
+/// leaves the previous config intact instead of a truncated one.
+pub fn save(app_handle: &AppHandle, config: &AppConfig) -> Result<(), String> {
+    use std::io::Write;
+
+    let path = config_path(app_handle)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory {:?}: {}", parent, e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(TEMP_FILE_SUFFIX);
+    let temp_path = PathBuf::from(temp_path);
+
+    let written = std::fs::File::create(&temp_path).and_then(|mut file| {
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| std::fs::rename(&temp_path, &path)) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(format!("Failed to write config {:?}: {}", path, e));
+    }
+    Ok(())
+}
+
+/// Apply `change` to the loaded config and persist it, returning the new config
+pub fn update(app_handle: &AppHandle, change: impl FnOnce(&mut AppConfig)) -> Result<AppConfig, String> {
+    let state: tauri::State<ConfigState> = app_handle.state();
+    let mut config = state.0.lock().unwrap();
+
+    let mut updated = config.clone();
+    change(&mut updated);
+    save(app_handle, &updated)?;
+
+    *config = updated.clone();
+    Ok(updated)
+}
+
+/// Whether the setup hook should start the backend: `STS_AUTOSTART` > config > on
+pub fn autostart_enabled(config: &AppConfig) -> Result<bool, BackendError> {
+    match env_var(AUTOSTART_ENV_VAR) {
+        Some(raw) => match raw.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(true),
+            "0" | "false" | "no" | "off" => Ok(false),
+            _ => Err(BackendError::InvalidConfig(format!(
+                "{}={:?} must be true or false",
+                AUTOSTART_ENV_VAR, raw
+            ))),
+        },
+        None => Ok(config.autostart.unwrap_or(true)),
+    }
+}
+
+/// Snapshot of the currently loaded config
+pub fn current(app_handle: &AppHandle) -> AppConfig {
+    let state: tauri::State<ConfigState> = app_handle.state();
+    let config = state.0.lock().unwrap();
+    config.clone()
+}
+
+/// Read an environment variable, treating unset and blank values the same
+pub fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
@@ -0,0 +1,75 @@
+use log::{debug, info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use crate::error::BackendError;
+
+/// Editors often write a file several times per save (truncate, write, rename);
+/// changes closer together than this count as one
+pub const DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// File the backend's settings read from its working directory (pydantic `env_file`,
+/// gui_backend/config.py)
+pub const BACKEND_ENV_FILE: &str = ".env";
+
+/// Watches one file for `set_config_watch`; dropping it stops the watch and closes the
+/// change channel
+pub type ConfigWatcher = notify::RecommendedWatcher;
+
+/// Watch `file` for changes. Its parent directory is watched rather than the file itself,
+/// so replacing the file (as most editors do on save) or creating it later is noticed too.
+pub fn watch(file: &Path) -> Result<(ConfigWatcher, UnboundedReceiver<()>), BackendError> {
+    let dir = file
+        .parent()
+        .filter(|dir| dir.is_dir())
+        .ok_or_else(|| BackendError::NotFound(format!("folder of {:?} does not exist", file)))?
+        .to_path_buf();
+    let name = file.file_name().map(|name| name.to_os_string());
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+        Ok(event) => {
+            let relevant = matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) && event
+                .paths
+                .iter()
+                .any(|path| path.file_name().map(|name| name.to_os_string()) == name);
+            if relevant {
+                debug!("Watched config changed: {:?}", event);
+                let _ = sender.send(());
+            }
+        }
+        Err(e) => warn!("Config watch error: {}", e),
+    })
+    .map_err(|e| BackendError::Io(format!("failed to start watching {:?}: {}", file, e)))?;
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| BackendError::Io(format!("failed to watch {:?}: {}", dir, e)))?;
+
+    info!("Watching {:?} for changes", file);
+    Ok((watcher, receiver))
+}
+
+/// Wait for the next burst of changes to settle, i.e. no further change for `DEBOUNCE`.
+/// Returns `false` once the watcher has been dropped.
+pub async fn settled(changes: &mut UnboundedReceiver<()>) -> bool {
+    if changes.recv().await.is_none() {
+        return false;
+    }
+    loop {
+        match tokio::time::timeout(DEBOUNCE, changes.recv()).await {
+            Ok(Some(())) => continue,
+            Ok(None) => return false,
+            Err(_) => return true,
+        }
+    }
+}
+
+/// The backend env file for a backend started in `working_dir`
+pub fn backend_env_file(working_dir: &Path) -> PathBuf {
+    working_dir.join(BACKEND_ENV_FILE)
+}
@@ -0,0 +1,115 @@
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+const HISTORY_FILE_NAME: &str = "crash_history.json";
+
+/// Crashes kept, oldest dropped first
+const MAX_CRASHES: usize = 20;
+
+/// Crashes older than this are pruned whenever the history is loaded or recorded to
+const MAX_CRASH_AGE_MS: u64 = 30 * 24 * 60 * 60 * 1000;
+
+/// Stderr lines kept with each crash
+const STDERR_SNIPPET_LINES: usize = 20;
+
+/// One backend crash, as reported in `backend-crashed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashRecord {
+    /// Unix time in milliseconds when the exit was noticed
+    pub at_ms: u64,
+    pub pid: u32,
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    /// The last stderr lines before the exit, oldest first
+    pub stderr: Vec<String>,
+}
+
+/// Recent backend crashes, persisted next to the config file so recurring crashes are
+/// visible across launches
+pub struct CrashHistory(Mutex<VecDeque<CrashRecord>>);
+
+fn history_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    use tauri::path::BaseDirectory;
+
+    app_handle
+        .path()
+        .resolve(HISTORY_FILE_NAME, BaseDirectory::AppLocalData)
+        .map_err(|e| format!("Failed to resolve crash history path: {}", e))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn prune(records: &mut VecDeque<CrashRecord>, now_ms: u64) {
+    records.retain(|record| now_ms.saturating_sub(record.at_ms) <= MAX_CRASH_AGE_MS);
+    while records.len() > MAX_CRASHES {
+        records.pop_front();
+    }
+}
+
+impl CrashHistory {
+    /// Load the saved history; a missing or unreadable file starts an empty one
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let records = history_path(app_handle)
+            .and_then(|path| std::fs::read_to_string(&path).map_err(|e| format!("{:?}: {}", path, e)))
+            .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()));
+        let mut records: VecDeque<CrashRecord> = match records {
+            Ok(records) => records,
+            Err(e) => {
+                debug!("No crash history loaded ({}); starting empty", e);
+                VecDeque::new()
+            }
+        };
+        prune(&mut records, now_ms());
+        CrashHistory(Mutex::new(records))
+    }
+
+    /// Append a crash and save the history. Failing to save is logged, never fatal.
+    pub fn record(
+        &self,
+        app_handle: &AppHandle,
+        pid: u32,
+        code: Option<i32>,
+        signal: Option<i32>,
+        recent_stderr: &[String],
+    ) {
+        let at_ms = now_ms();
+        let stderr = recent_stderr[recent_stderr.len().saturating_sub(STDERR_SNIPPET_LINES)..].to_vec();
+
+        let mut records = self.0.lock().unwrap();
+        records.push_back(CrashRecord {
+            at_ms,
+            pid,
+            code,
+            signal,
+            stderr,
+        });
+        prune(&mut records, at_ms);
+
+        let saved = history_path(app_handle).and_then(|path| {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("{:?}: {}", parent, e))?;
+            }
+            let contents = serde_json::to_string(&*records).map_err(|e| e.to_string())?;
+            std::fs::write(&path, contents).map_err(|e| format!("{:?}: {}", path, e))
+        });
+        if let Err(e) = saved {
+            warn!("Failed to save crash history: {}", e);
+        }
+    }
+
+    /// The most recent `limit` crashes (all if `None`), oldest first
+    pub fn snapshot(&self, limit: Option<usize>) -> Vec<CrashRecord> {
+        let records = self.0.lock().unwrap();
+        let skip = limit.map_or(0, |limit| records.len().saturating_sub(limit));
+        records.iter().skip(skip).cloned().collect()
+    }
+}
@@ -0,0 +1,61 @@
+use crate::config;
+use crate::error::BackendError;
+use crate::tools;
+
+/// Environment variable overriding the dev backend command, e.g.
+/// `poetry run sts-gui-server --port {port}` or `.venv/bin/python -m debugpy ... --port {port}`
+pub const DEV_BACKEND_CMD_ENV_VAR: &str = "STS_DEV_BACKEND_CMD";
+
+/// What `tauri dev` runs when no override is set
+const DEFAULT_DEV_BACKEND_CMD: &str = "uv run sts-gui-server --port {port}";
+
+/// Resolve the dev backend command into program + args, substituting `{port}` and
+/// `{workspace}`. Words are split on whitespace; single or double quotes group a word.
+pub fn resolve_command(port: u16, workspace: &str) -> Result<(String, Vec<String>), BackendError> {
+    let (template, source) = match config::env_var(DEV_BACKEND_CMD_ENV_VAR) {
+        Some(template) => (template, DEV_BACKEND_CMD_ENV_VAR),
+        None => (DEFAULT_DEV_BACKEND_CMD.to_string(), "default"),
+    };
+
+    let mut words = split_words(&template)
+        .map_err(|e| BackendError::InvalidConfig(format!("{} from {}: {}", e, source, template)))?
+        .into_iter()
+        .map(|word| {
+            word.replace("{port}", &port.to_string())
+                .replace("{workspace}", workspace)
+        });
+    let program = words.next().ok_or_else(|| {
+        BackendError::InvalidConfig(format!("{} must name a program to run", DEV_BACKEND_CMD_ENV_VAR))
+    })?;
+    // A bare `uv` may not be on the GUI's PATH; look in its usual install locations too
+    let program = if program == "uv" {
+        tools::resolve_uv().to_string_lossy().into_owned()
+    } else {
+        program
+    };
+    Ok((program, words.collect()))
+}
+
+fn split_words(template: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+
+    for c in template.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.get_or_insert_with(String::new).push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            None if c.is_whitespace() => words.extend(current.take()),
+            None => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("unterminated quote".to_string());
+    }
+    words.extend(current);
+    Ok(words)
+}
@@ -0,0 +1,52 @@
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::BackendError;
+use crate::tools;
+
+/// Values `set_backend_device` accepts, passed to the backend as `--device`
+pub const DEVICES: [&str; 3] = ["cpu", "gpu", "auto"];
+
+/// Device nodes NVIDIA (CUDA) and AMD (ROCm) drivers create on Linux
+const LINUX_GPU_NODES: [&str; 2] = ["/dev/nvidia0", "/dev/kfd"];
+
+/// Payload of `get_backend_device`
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceInfo {
+    /// The configured preference; `auto` when unset
+    pub device: String,
+    /// Whether a GPU the backend's models can use was found; `None` when it can't be told
+    pub gpu_detected: Option<bool>,
+}
+
+/// Normalize and check a device preference. `gpu` is refused only when this machine
+/// definitely has no usable GPU; when detection is inconclusive the backend decides.
+pub fn validate(device: &str) -> Result<String, BackendError> {
+    let device = device.trim().to_ascii_lowercase();
+    if !DEVICES.contains(&device.as_str()) {
+        return Err(BackendError::InvalidArgument(format!(
+            "unknown device {:?} (expected one of {})",
+            device,
+            DEVICES.join(", ")
+        )));
+    }
+    if device == "gpu" && gpu_detected() == Some(false) {
+        return Err(BackendError::Unsupported(
+            "no GPU the backend can use was found on this machine; choose cpu or auto".to_string(),
+        ));
+    }
+    Ok(device)
+}
+
+/// Best-effort GPU detection without loading any GPU library
+pub fn gpu_detected() -> Option<bool> {
+    if cfg!(target_os = "macos") {
+        // Apple silicon always has Metal; Intel Macs vary
+        return (std::env::consts::ARCH == "aarch64").then_some(true);
+    }
+    if cfg!(target_os = "linux") {
+        return Some(LINUX_GPU_NODES.iter().any(|node| Path::new(node).exists()));
+    }
+    // nvidia-smi ships with the NVIDIA driver; other vendors can't be ruled out
+    tools::find_on_path("nvidia-smi").map(|_| true)
+}
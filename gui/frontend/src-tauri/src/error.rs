@@ -0,0 +1,73 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Errors surfaced by backend lifecycle commands.
+///
+/// Serialized as `{ "kind": "...", "message": "..." }` so the frontend can branch
+/// on the kind while still showing a human-readable message.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum BackendError {
+    /// A port value from a command argument, env var, or config was unusable
+    InvalidPort(String),
+    /// Another start/restart is already running
+    OperationInProgress(String),
+    /// A configuration value (env var or config file) was unusable
+    InvalidConfig(String),
+    /// The backend did not respond in time
+    Timeout(String),
+    /// The backend is not running or refused the connection
+    Unavailable(String),
+    /// The backend process is alive and listening but can't be reached over loopback,
+    /// typically because a firewall or security product blocks the connection
+    Unreachable(String),
+    /// The backend answered with an error or an unexpected response
+    Http(String),
+    /// A command argument from the frontend was rejected before reaching the backend
+    InvalidArgument(String),
+    /// A requested file or directory does not exist
+    NotFound(String),
+    /// A path resolved to somewhere outside the workspace
+    OutsideWorkspace(String),
+    /// A local filesystem or OS operation failed
+    Io(String),
+    /// The OS refused access to a file or directory (including read-only volumes)
+    PermissionDenied(String),
+    /// The volume or the user's quota is full
+    StorageFull(String),
+    /// The running backend version lacks the requested feature
+    Unsupported(String),
+    /// The user cancelled the operation before it finished
+    Cancelled(String),
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackendError::InvalidPort(msg) => write!(f, "Invalid port: {}", msg),
+            BackendError::OperationInProgress(msg) => write!(f, "Operation in progress: {}", msg),
+            BackendError::InvalidConfig(msg) => write!(f, "Invalid configuration: {}", msg),
+            BackendError::Timeout(msg) => write!(f, "Timed out: {}", msg),
+            BackendError::Unavailable(msg) => write!(f, "Backend unavailable: {}", msg),
+            BackendError::Unreachable(msg) => write!(f, "Backend unreachable: {}", msg),
+            BackendError::Http(msg) => write!(f, "Backend request failed: {}", msg),
+            BackendError::InvalidArgument(msg) => write!(f, "Invalid argument: {}", msg),
+            BackendError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            BackendError::OutsideWorkspace(msg) => write!(f, "Outside workspace: {}", msg),
+            BackendError::Io(msg) => write!(f, "I/O error: {}", msg),
+            BackendError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            BackendError::StorageFull(msg) => write!(f, "Storage full: {}", msg),
+            BackendError::Unsupported(msg) => write!(f, "Not supported: {}", msg),
+            BackendError::Cancelled(msg) => write!(f, "Cancelled: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+// Lets commands that return `Result<_, String>` use `?` on BackendError
+impl From<BackendError> for String {
+    fn from(err: BackendError) -> Self {
+        err.to_string()
+    }
+}
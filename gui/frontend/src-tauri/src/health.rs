@@ -0,0 +1,202 @@
+use log::{debug, info, warn};
+use std::time::{Duration, Instant};
+
+use crate::api;
+use crate::config::{self, AppConfig};
+use crate::error::BackendError;
+
+/// Environment variable that overrides the readiness/health endpoint path
+pub const HEALTH_PATH_ENV_VAR: &str = "STS_HEALTH_PATH";
+pub const DEFAULT_HEALTH_PATH: &str = "/health";
+
+/// Host the backend binds to; it only ever listens on loopback
+pub const BACKEND_HOST: &str = "127.0.0.1";
+
+/// How long a single health request may take before it counts as a failure
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Upper bound on how long startup may take (cold PyInstaller starts can be slow)
+pub const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Environment variables that tune the readiness probe loop
+pub const HEALTH_RETRIES_ENV_VAR: &str = "STS_HEALTH_RETRIES";
+pub const HEALTH_INTERVAL_ENV_VAR: &str = "STS_HEALTH_INTERVAL_MS";
+
+/// Default delay between readiness probes while the backend is starting
+const DEFAULT_PROBE_INTERVAL_MS: u64 = 250;
+/// Default number of readiness probes; with the default interval this spans `READY_TIMEOUT`
+const DEFAULT_PROBE_RETRIES: u64 = 240;
+
+/// Accepted ranges; values outside are clamped rather than rejected
+const PROBE_INTERVAL_RANGE_MS: (u64, u64) = (50, 10_000);
+const PROBE_RETRIES_RANGE: (u64, u64) = (1, 10_000);
+
+/// How often and how many times readiness is probed. `READY_TIMEOUT` still caps the total.
+#[derive(Debug, Clone, Copy)]
+pub struct ProbeSettings {
+    pub retries: u32,
+    pub interval: Duration,
+}
+
+/// Resolve probe settings: env var > config file > default, each validated and clamped
+pub fn resolve_probe_settings(config: &AppConfig) -> Result<ProbeSettings, BackendError> {
+    let retries = resolve_number(
+        HEALTH_RETRIES_ENV_VAR,
+        config.health_retries.map(u64::from),
+        DEFAULT_PROBE_RETRIES,
+        PROBE_RETRIES_RANGE,
+    )?;
+    let interval_ms = resolve_number(
+        HEALTH_INTERVAL_ENV_VAR,
+        config.health_interval_ms,
+        DEFAULT_PROBE_INTERVAL_MS,
+        PROBE_INTERVAL_RANGE_MS,
+    )?;
+
+    Ok(ProbeSettings {
+        retries: retries as u32,
+        interval: Duration::from_millis(interval_ms),
+    })
+}
+
+/// Environment variables that tune the unresponsive-backend watchdog
+pub const WATCHDOG_INTERVAL_ENV_VAR: &str = "STS_WATCHDOG_INTERVAL_MS";
+pub const WATCHDOG_THRESHOLD_ENV_VAR: &str = "STS_WATCHDOG_THRESHOLD";
+
+const DEFAULT_WATCHDOG_INTERVAL_MS: u64 = 5_000;
+const DEFAULT_WATCHDOG_THRESHOLD: u64 = 3;
+const WATCHDOG_INTERVAL_RANGE_MS: (u64, u64) = (1_000, 300_000);
+const WATCHDOG_THRESHOLD_RANGE: (u64, u64) = (1, 100);
+
+/// How often the watchdog probes a running backend, and after how many consecutive
+/// failures it reports it as unresponsive
+#[derive(Debug, Clone, Copy)]
+pub struct WatchdogSettings {
+    pub interval: Duration,
+    pub failure_threshold: u32,
+}
+
+/// Resolve watchdog settings: env var > config file > default, each validated and clamped
+pub fn resolve_watchdog_settings(config: &AppConfig) -> Result<WatchdogSettings, BackendError> {
+    let interval_ms = resolve_number(
+        WATCHDOG_INTERVAL_ENV_VAR,
+        config.watchdog_interval_ms,
+        DEFAULT_WATCHDOG_INTERVAL_MS,
+        WATCHDOG_INTERVAL_RANGE_MS,
+    )?;
+    let threshold = resolve_number(
+        WATCHDOG_THRESHOLD_ENV_VAR,
+        config.watchdog_failure_threshold.map(u64::from),
+        DEFAULT_WATCHDOG_THRESHOLD,
+        WATCHDOG_THRESHOLD_RANGE,
+    )?;
+
+    Ok(WatchdogSettings {
+        interval: Duration::from_millis(interval_ms),
+        failure_threshold: threshold as u32,
+    })
+}
+
+fn resolve_number(
+    env_var: &str,
+    config: Option<u64>,
+    default: u64,
+    (min, max): (u64, u64),
+) -> Result<u64, BackendError> {
+    let (value, source) = match config::env_var(env_var) {
+        Some(raw) => {
+            let value = raw.parse::<u64>().map_err(|_| {
+                BackendError::InvalidConfig(format!("{}={:?} is not a number", env_var, raw))
+            })?;
+            (value, env_var)
+        }
+        None => match config {
+            Some(value) => (value, "config file"),
+            None => return Ok(default),
+        },
+    };
+
+    let clamped = value.clamp(min, max);
+    if clamped != value {
+        warn!(
+            "{} from {} is out of range ({}-{}); using {}",
+            value, source, min, max, clamped
+        );
+    }
+    Ok(clamped)
+}
+
+/// Resolve the health endpoint path: `STS_HEALTH_PATH` env var > config file > `/health`
+pub fn resolve_health_path(config: &AppConfig) -> Result<String, BackendError> {
+    let (path, source) = match config::env_var(HEALTH_PATH_ENV_VAR) {
+        Some(path) => (path, HEALTH_PATH_ENV_VAR),
+        None => match config.health_path.as_deref().map(str::trim) {
+            Some(path) if !path.is_empty() => (path.to_string(), "config file"),
+            _ => return Ok(DEFAULT_HEALTH_PATH.to_string()),
+        },
+    };
+
+    if !path.starts_with('/') {
+        return Err(BackendError::InvalidConfig(format!(
+            "health path {:?} from {} must start with '/'",
+            path, source
+        )));
+    }
+    Ok(path)
+}
+
+/// Build a URL for `path` on the local backend
+pub fn backend_url(port: u16, path: &str) -> String {
+    format!("http://{}:{}{}", BACKEND_HOST, port, path)
+}
+
+/// Issue a single health request, returning the round-trip time on a 2xx response
+pub async fn probe(port: u16, health_path: &str) -> Result<Duration, String> {
+    let started = Instant::now();
+    let response = api::client(PROBE_TIMEOUT)?
+        .get(backend_url(port, health_path))
+        .send()
+        .await
+        .map_err(|e| format!("Health check failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Health check returned HTTP {}", response.status()));
+    }
+    Ok(started.elapsed())
+}
+
+/// Poll the health endpoint until it answers, the retries run out, or `timeout` elapses
+pub async fn wait_for_backend_ready(
+    port: u16,
+    health_path: &str,
+    settings: ProbeSettings,
+    timeout: Duration,
+) -> Result<Duration, BackendError> {
+    let started = Instant::now();
+    let mut last_error = String::new();
+
+    for _ in 0..settings.retries {
+        if started.elapsed() >= timeout {
+            break;
+        }
+        match probe(port, health_path).await {
+            Ok(_) => {
+                let elapsed = started.elapsed();
+                info!("Backend ready on port {} after {:?}", port, elapsed);
+                return Ok(elapsed);
+            }
+            Err(e) => {
+                debug!("Backend not ready yet: {}", e);
+                last_error = e;
+            }
+        }
+        tokio::time::sleep(settings.interval).await;
+    }
+
+    Err(BackendError::Timeout(format!(
+        "backend on port {} did not become ready after {} probes / {:?} (last error: {})",
+        port,
+        settings.retries,
+        started.elapsed(),
+        last_error
+    )))
+}
@@ -0,0 +1,80 @@
+use log::{info, warn};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::BackendError;
+
+/// SHA-256 of the sidecar this build was made with, embedded by `build.rs`.
+/// Empty when the app was built before a sidecar existed (e.g. dev builds).
+const EXPECTED_SIDECAR_SHA256: &str = env!("STS_SIDECAR_SHA256");
+
+/// Outcome of `verify`
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub path: String,
+    pub sha256: String,
+    pub size_bytes: u64,
+}
+
+/// Hash `sidecar` and compare it with the checksum embedded at build time.
+///
+/// Fails with `Unsupported` when the build carries no checksum, and with `InvalidConfig`
+/// on a mismatch, since a truncated or altered binary would otherwise fail to spawn with
+/// an unrelated-looking error.
+pub fn verify(sidecar: &Path) -> Result<IntegrityReport, BackendError> {
+    if EXPECTED_SIDECAR_SHA256.is_empty() {
+        return Err(BackendError::Unsupported(
+            "this build has no embedded sidecar checksum".to_string(),
+        ));
+    }
+
+    let (sha256, size_bytes) = sha256_file(sidecar)?;
+    if !sha256.eq_ignore_ascii_case(EXPECTED_SIDECAR_SHA256) {
+        warn!(
+            "Sidecar {:?} checksum mismatch: expected {}, found {} ({} bytes)",
+            sidecar, EXPECTED_SIDECAR_SHA256, sha256, size_bytes
+        );
+        return Err(BackendError::InvalidConfig(format!(
+            "the bundled backend at {:?} is corrupted or has been modified \
+             (checksum {} does not match this build); reinstall the app",
+            sidecar, sha256
+        )));
+    }
+
+    info!("Sidecar {:?} checksum verified ({} bytes)", sidecar, size_bytes);
+    Ok(IntegrityReport {
+        path: sidecar.display().to_string(),
+        sha256,
+        size_bytes,
+    })
+}
+
+/// Hex SHA-256 and size of `path`, read in chunks so large binaries aren't held in memory
+fn sha256_file(path: &Path) -> Result<(String, u64), BackendError> {
+    let io_error = |e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::NotFound => BackendError::NotFound(format!("{:?} does not exist", path)),
+        _ => BackendError::Io(format!("failed to read {:?}: {}", path, e)),
+    };
+
+    let mut file = std::fs::File::open(path).map_err(io_error)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let read = file.read(&mut buffer).map_err(io_error)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+
+    let digest = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Ok((digest, size))
+}
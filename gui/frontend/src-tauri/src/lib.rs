@@ -1,18 +1,99 @@
+mod api;
+mod architecture;
+mod config;
+mod config_watch;
+mod crash_history;
+mod dev_backend;
+mod device;
+mod error;
+mod health;
+mod integrity;
+mod limits;
+mod logs;
+mod metrics;
+mod operation;
+mod permissions;
+mod port;
+mod power;
+mod processes;
+mod progress;
+mod quarantine;
+mod screenshot;
+mod scripts;
+mod secrets;
+mod session;
+mod shutdown;
+mod startup_history;
+mod tools;
+mod workspace;
+
 use log::{debug, error, info, warn};
+use serde::Serialize;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::Mutex;
-use tauri::{AppHandle, Manager, State};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+use tauri_plugin_opener::OpenerExt;
+use tauri_plugin_window_state::StateFlags;
 use tauri_plugin_shell::{process::CommandChild, ShellExt};
 
-#[cfg(debug_assertions)]
+use api::{AudioOutputConfig, BackendCapabilities, BenchmarkResult, CredentialTestResult, ProxyResponse, WarmupResult};
+use config::{ConfigState, EffectiveConfig};
+use crash_history::{CrashHistory, CrashRecord};
+use error::BackendError;
+use logs::{BackendStderr, LastBackendError, LogPipeline, LogStream};
+use metrics::{MetricsSnapshot, RequestMetrics};
+use operation::{BackendOperation, OperationGuard};
+use progress::{JobProgress, LatestJobProgress};
+use startup_history::{StartupHistory, StartupRecord};
+
 use tauri_plugin_shell::process::CommandEvent;
 
+/// Upper bound on cancelling backend jobs while the app quits
+const EXIT_CANCEL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Environment variable that forces `dev` or `prod` instead of sidecar detection
+const FORCE_MODE_ENV_VAR: &str = "STS_FORCE_MODE";
+
 // Port configuration constants
 const DEV_PORT: u16 = 8000;
 const PROD_PORT: u16 = 58735;
 
+/// How long `prepare_for_update` (by default), `relaunch_app` and `stop_and_archive_logs`
+/// wait for the backend to exit
+const DEFAULT_UPDATE_STOP_TIMEOUT_MS: u64 = 10_000;
+
+/// Workspace folder `stop_and_archive_logs` archives into
+const LOG_ARCHIVE_DIR_NAME: &str = "logs";
+
+/// With `show_window_on_ready`, show the window anyway after this long so a backend
+/// that never becomes ready can't leave the app invisible
+const WINDOW_SHOW_FALLBACK: Duration = Duration::from_secs(10);
+
+/// Env vars the backend's HTTP clients (requests, httpx, provider SDKs) read the proxy from
+const PROXY_ENV_VARS: [&str; 4] = ["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"];
+/// Hosts that must never go through the proxy: the GUI talks to the backend over loopback
+const NO_PROXY_HOSTS: &str = "127.0.0.1,localhost,::1";
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// Upper bound for `set_backend_workers`, regardless of how many CPUs are available
+const MAX_BACKEND_WORKERS: u32 = 16;
+
+/// `request_backend_dump` stops collecting once stderr has been quiet this long,
+/// or after `STACK_DUMP_MAX_WAIT` at most
+#[cfg(unix)]
+const STACK_DUMP_QUIET: Duration = Duration::from_millis(300);
+#[cfg(unix)]
+const STACK_DUMP_MAX_WAIT: Duration = Duration::from_secs(3);
+
+/// While a start/restart is in flight, `backend_request` retries refused connections
+/// for up to this long instead of failing on the startup race
+const STARTUP_RETRY_WINDOW: Duration = Duration::from_secs(15);
+const STARTUP_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
 /// Represents the backend process, which can be either:
 /// - Dev: Manually spawned via `uv run` (std::process::Child)
 /// - Sidecar: Tauri-managed executable bundled with the app (CommandChild)
@@ -28,10 +109,14 @@ impl BackendChild {
     /// The caller should ensure they take() the Option<BackendChild> from the Mutex before calling this.
     fn kill(self) -> std::io::Result<()> {
         match self {
-            BackendChild::Dev(mut child) => child.kill(),
+            BackendChild::Dev(mut child) => {
+                child.kill()?;
+                // Reap immediately so the process doesn't linger as a zombie
+                child.wait().map(|_| ())
+            }
             BackendChild::Sidecar(child) => {
                 // CommandChild::kill() takes ownership and returns Result<(), Error>
-                child.kill().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+                child.kill().map_err(std::io::Error::other)
             }
         }
     }
@@ -57,30 +142,503 @@ impl BackendChild {
     }
 }
 
+impl shutdown::Stoppable for BackendChild {
+    fn pid(&self) -> u32 {
+        BackendChild::pid(self)
+    }
+
+    fn stop(self) -> std::io::Result<()> {
+        self.kill()
+    }
+}
+
+/// Whether the backend runs as the bundled sidecar or via `uv run` from the project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BackendMode {
+    Production,
+    Development,
+}
+
+impl BackendMode {
+    /// Accepts `dev`/`development` and `prod`/`production`, case-insensitively
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dev" | "development" => Some(BackendMode::Development),
+            "prod" | "production" => Some(BackendMode::Production),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            BackendMode::Production => "production",
+            BackendMode::Development => "development",
+        }
+    }
+
+    /// Capitalized name for display, e.g. in the status bar
+    fn label(self) -> &'static str {
+        match self {
+            BackendMode::Production => "Production",
+            BackendMode::Development => "Development",
+        }
+    }
+
+    fn is_bundled(self) -> bool {
+        self == BackendMode::Production
+    }
+}
+
+/// A spawned backend together with the settings it was launched with
+struct RunningBackend {
+    child: BackendChild,
+    mode: BackendMode,
+    port: u16,
+    started_at: Instant,
+    /// Set once the health endpoint has answered after this spawn
+    ready: bool,
+    /// Fetched on first request and kept for the life of this process
+    capabilities: Option<BackendCapabilities>,
+    /// Fetched once the backend is ready; `get_backend_audio_config(refresh)` re-reads it
+    audio_config: Option<AudioOutputConfig>,
+    /// `--profile` / `--workers` this process was started with
+    profile: Option<String>,
+    workers: Option<u32>,
+    launch_command: LaunchCommand,
+    /// Set by `quiesce_backend`: the server has stopped serving but the process lives on
+    quiesced: bool,
+    /// Caps applied at spawn, to tell a limit kill from a crash
+    resource_limits: limits::ResourceLimits,
+}
+
+/// How a backend was spawned, for support requests
+#[derive(Debug, Clone, Serialize)]
+struct LaunchCommand {
+    program: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    /// Variables set on top of the inherited environment; secret values are redacted
+    env: Vec<(String, String)>,
+}
+
+impl LaunchCommand {
+    fn new(program: String, args: &[String], cwd: Option<&str>, env: &[(&str, String)], secrets: &[&str]) -> Self {
+        LaunchCommand {
+            program,
+            args: args.to_vec(),
+            cwd: cwd.map(str::to_string),
+            env: env
+                .iter()
+                .map(|(name, value)| {
+                    let value = if secrets.contains(name) { "<redacted>".to_string() } else { value.clone() };
+                    (name.to_string(), value)
+                })
+                .collect(),
+        }
+    }
+}
+
 // Global state to track the backend process
-struct BackendProcess(Mutex<Option<BackendChild>>);
+struct BackendProcess {
+    child: Mutex<Option<RunningBackend>>,
+    /// Set while a start/restart is running so overlapping calls are rejected
+    /// instead of spawning duplicate backends
+    operation: Mutex<Option<BackendOperation>>,
+    /// Present while `launch_backend` waits for readiness; `abort_startup` fires it
+    startup_abort: Mutex<Option<tokio::sync::oneshot::Sender<()>>>,
+    /// Held for the duration of `run_backend_benchmark` so runs never overlap
+    benchmark: tokio::sync::Mutex<()>,
+    /// Present while `stream_generation_to_file` runs; `cancel_generation_download` fires it
+    generation_download: Mutex<Option<tokio::sync::oneshot::Sender<()>>>,
+    /// Notified whenever the process, its readiness or the current operation changes
+    state_changed: tokio::sync::Notify,
+    /// Set via `set_maintenance_mode` while the user works on the backend by hand
+    maintenance: AtomicBool,
+    /// Port of the last backend that became ready, kept after it stops or crashes
+    last_port: Mutex<Option<u16>>,
+    /// How that port was arrived at, for `get_port_resolution`
+    port_resolution: Mutex<Option<PortResolution>>,
+}
+
+/// Lifecycle state as seen from outside, derived from `BackendProcess`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BackendState {
+    Stopped,
+    Starting,
+    Ready,
+    Stopping,
+}
+
+impl BackendState {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "stopped" => Some(BackendState::Stopped),
+            "starting" => Some(BackendState::Starting),
+            "ready" => Some(BackendState::Ready),
+            "stopping" => Some(BackendState::Stopping),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            BackendState::Stopped => "stopped",
+            BackendState::Starting => "starting",
+            BackendState::Ready => "ready",
+            BackendState::Stopping => "stopping",
+        }
+    }
+}
+
+impl BackendProcess {
+    fn lifecycle_state(&self) -> BackendState {
+        // Same lock order as get_health_summary: child, then operation
+        let process = self.child.lock().unwrap();
+        let operation = *self.operation.lock().unwrap();
+        match (process.as_ref(), operation) {
+            (Some(_), Some(BackendOperation::Stopping)) => BackendState::Stopping,
+            (Some(running), _) if running.ready => BackendState::Ready,
+            (Some(_), _) => BackendState::Starting,
+            (
+                None,
+                Some(BackendOperation::Starting | BackendOperation::Restarting | BackendOperation::SwitchingWorkspace),
+            ) => BackendState::Starting,
+            (None, _) => BackendState::Stopped,
+        }
+    }
+
+    /// Wake `await_backend_state` callers; they re-derive the state themselves
+    fn notify_state_changed(&self) {
+        self.state_changed.notify_waiters();
+    }
+
+    /// Claim the operation slot, failing if another lifecycle operation already holds it
+    fn begin_operation(&self, op: BackendOperation) -> Result<OperationGuard<'_>, BackendError> {
+        OperationGuard::begin(&self.operation, &self.state_changed, op)
+    }
+}
+
+/// Tag for a backend's captured output, e.g. `production:58735` (see `logs::SourceLogs`)
+fn log_source(mode: BackendMode, port: u16) -> Arc<str> {
+    format!("{}:{}", mode.as_str(), port).into()
+}
 
 /// Helper function to shutdown backend process
 /// Extracts common cleanup logic used in stop_backend and RunEvent::Exit
-fn shutdown_backend(app_handle: &AppHandle) {
+/// Returns the PIDs that were signalled, empty if there was no backend to stop.
+///
+/// Everything tracked is taken out of state first (`shutdown::take_all`) and then stopped
+/// with `shutdown::stop_all`, so the lock is never held across a kill and other commands
+/// observe "stopped" straight away. The log flush runs in the background; on exit the
+/// Exit handler flushes again synchronously.
+fn shutdown_backend(app_handle: &AppHandle) -> Vec<u32> {
     let state: State<BackendProcess> = app_handle.state();
-    let mut guard = state.0.lock().unwrap();
-    if let Some(child) = guard.take() {
-        let pid = child.pid();
-        info!("Killing backend process (PID: {})...", pid);
-        if let Err(e) = child.kill() {
-            warn!("Failed to kill backend process: {}", e);
-        } else {
-            info!("Backend process killed successfully");
-        }
-    } else {
+    let taken = shutdown::take_all(&state.child);
+    state.notify_state_changed();
+
+    let stopped = shutdown::stop_all(
+        taken
+            .into_iter()
+            .map(|running| (log_source(running.mode, running.port), running.child)),
+    );
+    if stopped.is_empty() {
         debug!("No backend process to clean up");
+        return Vec::new();
+    }
+    for shutdown::Stopped { name, pid, result } in &stopped {
+        match result {
+            Ok(()) => info!("Backend {} (PID: {}) killed successfully", name, pid),
+            Err(e) => warn!("Failed to kill backend {} (PID: {}): {}", name, pid, e),
+        }
+    }
+    // Callers are often async commands, so wait for the last lines off the current thread
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn(async move { app.state::<LogPipeline>().flush_async().await });
+    stopped.iter().map(|stopped| stopped.pid).collect()
+}
+
+/// Payload of the `backend-crashed` event
+#[derive(Debug, Clone, Serialize)]
+struct BackendCrash {
+    pid: u32,
+    code: Option<i32>,
+    signal: Option<i32>,
+    /// True when `get_last_backend_error` has stderr output to show
+    has_error_details: bool,
+    /// Maintenance mode is on, so listeners should not restart the backend
+    maintenance: bool,
+}
+
+/// Record that backend `pid` exited. If it is still the tracked backend, nobody asked it
+/// to stop (shutdown_backend takes it out of state before killing), so it's a crash.
+fn handle_backend_exit(app_handle: &AppHandle, pid: u32, code: Option<i32>, signal: Option<i32>) {
+    let state: State<BackendProcess> = app_handle.state();
+    let resource_limits = {
+        let mut guard = state.child.lock().unwrap();
+        match guard.take_if(|running| running.child.pid() == pid) {
+            Some(running) => running.resource_limits,
+            None => {
+                debug!("Backend (PID: {}) exited after an intentional stop", pid);
+                return;
+            }
+        }
+    };
+    state.notify_state_changed();
+
+    let stderr_buffer: State<BackendStderr> = app_handle.state();
+    let last_error = stderr_buffer.last_error();
+    app_handle.state::<CrashHistory>().record(
+        app_handle,
+        pid,
+        code,
+        signal,
+        last_error.as_ref().map_or(&[], |last_error| last_error.recent_stderr.as_slice()),
+    );
+    let crash = BackendCrash {
+        pid,
+        code,
+        signal,
+        has_error_details: last_error.is_some(),
+        maintenance: state.maintenance.load(Ordering::Relaxed),
+    };
+    error!("Backend crashed: {:?}", crash);
+    let recent_stderr = last_error.as_ref().map_or(&[][..], |last_error| last_error.recent_stderr.as_slice());
+    if let Some(limit) = resource_limits.hit_by(code, signal, recent_stderr) {
+        warn!("Backend (PID: {}) was stopped by its {} limit", pid, limit);
+        let hit = limits::ResourceLimitHit {
+            pid,
+            limit,
+            limits: resource_limits,
+        };
+        if let Err(e) = app_handle.emit("backend-resource-limit", &hit) {
+            warn!("Failed to emit backend-resource-limit event: {}", e);
+        }
+    }
+    if let Err(e) = app_handle.emit("backend-crashed", &crash) {
+        warn!("Failed to emit backend-crashed event: {}", e);
+    }
+}
+
+/// Collect the exit status of a dev backend whose output stream just closed.
+/// std::process::Child has no exit notification, so poll briefly for the status.
+fn reap_dev_backend(app_handle: &AppHandle, pid: u32) {
+    for _ in 0..20 {
+        let status = {
+            let state: State<BackendProcess> = app_handle.state();
+            let mut guard = state.child.lock().unwrap();
+            match *guard {
+                Some(ref mut running) if running.child.pid() == pid => running.child.try_wait(),
+                // Already stopped intentionally or replaced by a new backend
+                _ => return,
+            }
+        };
+
+        match status {
+            Ok(Some(status)) => {
+                #[cfg(unix)]
+                let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+                #[cfg(not(unix))]
+                let signal = None;
+                handle_backend_exit(app_handle, pid, status.code(), signal);
+                return;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(e) => {
+                warn!("Error checking backend status: {}", e);
+                return;
+            }
+        }
+    }
+    warn!("Backend (PID: {}) closed stderr but is still running", pid);
+}
+
+/// Show and focus the main window (no-op if it is already visible)
+fn show_main_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            return;
+        }
+        if let Err(e) = window.show() {
+            warn!("Failed to show main window: {}", e);
+        }
+        let _ = window.set_focus();
+    }
+}
+
+/// Launches forwarded to this process by the single-instance plugin
+#[derive(Default)]
+struct InstanceState {
+    secondary_launches: AtomicU32,
+    last_secondary_launch: Mutex<Option<SecondaryLaunch>>,
+}
+
+/// A later launch that was redirected to this process
+#[derive(Debug, Clone, Serialize)]
+struct SecondaryLaunch {
+    /// When it was forwarded (ms since the Unix epoch)
+    at_ms: u64,
+    args: Vec<String>,
+}
+
+/// The active `set_config_watch` watcher; `None` while watching is off (the default)
+#[derive(Default)]
+struct ConfigWatchState(Mutex<Option<config_watch::ConfigWatcher>>);
+
+/// Payload of `config-watch-restarting` and `config-watch-restarted`
+#[derive(Debug, Clone, Serialize)]
+struct ConfigWatchRestart {
+    path: String,
+    /// Set on `config-watch-restarted` when the restart failed
+    error: Option<String>,
+}
+
+/// Result of `is_primary_instance`
+#[derive(Debug, Clone, Serialize)]
+struct InstanceInfo {
+    /// Always true; see `is_primary_instance`
+    primary: bool,
+    /// Later launches that were forwarded here instead of starting their own backend
+    secondary_launches: u32,
+    /// The most recent of those, if any
+    last_secondary_launch: Option<SecondaryLaunch>,
+}
+
+/// Single-instance callback: another launch was redirected here, so bring the window up
+fn handle_secondary_launch(app_handle: &AppHandle, args: Vec<String>) {
+    let instance = app_handle.state::<InstanceState>();
+    let launches = instance.secondary_launches.fetch_add(1, Ordering::Relaxed) + 1;
+    info!("Another instance was launched (args: {:?}); focusing this one ({} so far)", args, launches);
+    let at_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+    *instance.last_secondary_launch.lock().unwrap() = Some(SecondaryLaunch { at_ms, args });
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.unminimize();
+        if let Err(e) = window.show() {
+            warn!("Failed to show main window: {}", e);
+        }
+        let _ = window.set_focus();
+    }
+}
+
+/// After the window-state plugin restores a saved position, make sure the window can still
+/// be reached: if either top corner (where the title bar is) is off every monitor, e.g.
+/// after unplugging a display, move and if necessary shrink it onto the nearest monitor.
+fn clamp_main_window_onscreen(app_handle: &AppHandle) -> tauri::Result<()> {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        return Ok(());
+    };
+
+    let position = window.outer_position()?;
+    let size = window.outer_size()?;
+    let monitors = window.available_monitors()?;
+    let on_screen = |x: i32, y: i32| {
+        monitors.iter().any(|monitor| {
+            let origin = monitor.position();
+            let extent = monitor.size();
+            x >= origin.x
+                && x < origin.x + extent.width as i32
+                && y >= origin.y
+                && y < origin.y + extent.height as i32
+        })
+    };
+    if on_screen(position.x, position.y) && on_screen(position.x + size.width as i32 - 1, position.y) {
+        return Ok(());
+    }
+
+    let Some(monitor) = window.current_monitor()?.or(window.primary_monitor()?) else {
+        return Ok(());
+    };
+    let (origin, extent) = (*monitor.position(), *monitor.size());
+    let width = size.width.min(extent.width);
+    let height = size.height.min(extent.height);
+    let x = position.x.clamp(origin.x, origin.x + (extent.width - width) as i32);
+    let y = position.y.clamp(origin.y, origin.y + (extent.height - height) as i32);
+
+    info!("Saved window position is off-screen; moving to ({}, {})", x, y);
+    if (width, height) != (size.width, size.height) {
+        window.set_size(tauri::PhysicalSize::new(width, height))?;
     }
+    window.set_position(tauri::PhysicalPosition::new(x, y))
+}
+
+/// Stop the backend and exit when the GUI process receives SIGTERM (containers,
+/// process managers), which doesn't reliably produce `RunEvent::Exit` on its own.
+/// The Exit handler still runs afterwards but finds no backend left to stop.
+#[cfg(unix)]
+fn install_sigterm_handler(app_handle: AppHandle) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tauri::async_runtime::spawn(async move {
+        let mut sigterm = match signal(SignalKind::terminate()) {
+            Ok(sigterm) => sigterm,
+            Err(e) => {
+                warn!("Failed to install SIGTERM handler: {}", e);
+                return;
+            }
+        };
+
+        if sigterm.recv().await.is_some() {
+            info!("Received SIGTERM, shutting down backend before exit");
+            cancel_jobs_before_exit(&app_handle).await;
+            shutdown_backend(&app_handle);
+            app_handle.exit(0);
+        }
+    });
+}
+
+/// Payload of the `system-resumed` event
+#[derive(Debug, Clone, Serialize)]
+struct SystemResumed {
+    slept_ms: u64,
+    /// `none` or `restarted`
+    action: &'static str,
+    /// Set when the configured action failed
+    error: Option<String>,
+}
+
+/// Apply `restart_on_resume` whenever the machine wakes up
+/// (see `power::watch_for_resume` for how wakes are detected and its limits)
+fn install_resume_handler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(power::watch_for_resume(move |slept| {
+        let app_handle = app_handle.clone();
+        async move {
+            let restart = config::current(&app_handle).restart_on_resume.unwrap_or(false);
+            let running = app_handle.state::<BackendProcess>().child.lock().unwrap().is_some();
+
+            let (action, result) = if running && restart {
+                ("restarted", restart_backend(app_handle.clone(), None).await.map(|_| ()))
+            } else {
+                ("none", Ok(()))
+            };
+            if let Err(e) = &result {
+                warn!("Backend {} after resume failed: {}", action, e);
+            }
+
+            let resumed = SystemResumed {
+                slept_ms: slept.as_millis() as u64,
+                action,
+                error: result.err(),
+            };
+            if let Err(e) = app_handle.emit("system-resumed", &resumed) {
+                warn!("Failed to emit system-resumed event: {}", e);
+            }
+        }
+    }));
 }
 
 /// Get the workspace directory path for the application.
 /// Uses runtime detection: bundled apps use Application Support, dev mode uses project root.
 fn get_workspace_dir(app_handle: &AppHandle, is_bundled: bool) -> Result<std::path::PathBuf, String> {
+    // A location chosen via `migrate_workspace` wins over the per-mode default
+    if let Some(dir) = config::current(app_handle).workspace_dir {
+        return Ok(PathBuf::from(dir));
+    }
+
     if is_bundled {
         // Bundled mode (production): use Application Support directory (standard for app-managed data)
         // This directory is automatically accessible within the app sandbox
@@ -90,12 +648,30 @@ fn get_workspace_dir(app_handle: &AppHandle, is_bundled: bool) -> Result<std::pa
         //   - Linux: ~/.local/share/script-to-speech/
         use tauri::path::BaseDirectory;
 
-        let app_data_dir = app_handle
-            .path()
-            .resolve("", BaseDirectory::AppLocalData)
-            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+        // If the platform can't resolve it, fall back to AppData and then the temp dir so the
+        // app stays usable; only a failure of every option is an error
+        let local_data_error = match app_handle.path().resolve("", BaseDirectory::AppLocalData) {
+            Ok(dir) => return Ok(dir),
+            Err(e) => e,
+        };
+        warn!("Failed to resolve AppLocalData ({}); falling back to AppData", local_data_error);
+
+        let app_data_error = match app_handle.path().resolve("", BaseDirectory::AppData) {
+            Ok(dir) => return Ok(dir),
+            Err(e) => e,
+        };
+        warn!("Failed to resolve AppData ({}); falling back to the temp directory", app_data_error);
 
-        Ok(app_data_dir)
+        let temp_dir = std::env::temp_dir();
+        if temp_dir.as_os_str().is_empty() {
+            return Err(format!(
+                "Failed to get app data directory: AppLocalData ({}), AppData ({}) and the temp directory are all unavailable",
+                local_data_error, app_data_error
+            ));
+        }
+        let workspace_dir = temp_dir.join(&app_handle.config().identifier);
+        warn!("Using temporary workspace {:?}; files may be removed by the OS", workspace_dir);
+        Ok(workspace_dir)
     } else {
         // Development mode: use compile-time constant set by build.rs
         // This eliminates fragile runtime path traversal
@@ -103,223 +679,3295 @@ fn get_workspace_dir(app_handle: &AppHandle, is_bundled: bool) -> Result<std::pa
     }
 }
 
+/// Decide between the dev and bundled backend, returning the mode and where it came from.
+/// Precedence: `STS_FORCE_MODE` env var > config `force_mode` > sidecar detection.
+fn resolve_backend_mode(app_handle: &AppHandle) -> Result<(BackendMode, &'static str), BackendError> {
+    let forced = config::env_var(FORCE_MODE_ENV_VAR)
+        .map(|value| (value, FORCE_MODE_ENV_VAR))
+        .or_else(|| config::current(app_handle).force_mode.map(|value| (value, "config file")));
+
+    if let Some((value, source)) = forced {
+        let mode = BackendMode::parse(&value).ok_or_else(|| {
+            BackendError::InvalidConfig(format!(
+                "backend mode {:?} from {} must be \"dev\" or \"prod\"",
+                value, source
+            ))
+        })?;
+        return Ok((mode, source));
+    }
+
+    // Runtime detection: try to create sidecar command to determine if we're bundled
+    if app_handle.shell().sidecar("sts-gui-backend").is_ok() {
+        Ok((BackendMode::Production, "sidecar detection"))
+    } else {
+        Ok((BackendMode::Development, "sidecar detection"))
+    }
+}
+
+/// Default port for the given mode, before any overrides are applied
+fn default_port(is_bundled: bool) -> u16 {
+    if is_bundled {
+        PROD_PORT
+    } else {
+        DEV_PORT
+    }
+}
+
+/// Startup diagnostics returned by `start_backend` / `restart_backend`
+#[derive(Debug, Clone, Serialize)]
+struct StartupTelemetry {
+    mode: BackendMode,
+    pid: u32,
+    port: u16,
+    /// Time from spawn until the health endpoint answered
+    startup_ms: u64,
+    /// True if an existing backend was reused instead of spawning a new one
+    already_running: bool,
+}
+
+/// What `spawn_backend` did, for building `StartupTelemetry`
+struct SpawnOutcome {
+    mode: BackendMode,
+    pid: u32,
+    port: u16,
+    spawned_at: Instant,
+    already_running: bool,
+}
+
 #[tauri::command]
-async fn start_backend(app_handle: AppHandle) -> Result<String, String> {
+async fn start_backend(app_handle: AppHandle, port: Option<u32>) -> Result<StartupTelemetry, String> {
     info!("Starting FastAPI backend server");
 
     let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::Starting)?;
 
-    // Hold lock through check and spawn to prevent race condition
-    // If two threads call start_backend simultaneously, only one will spawn
-    let mut process = backend_state.0.lock().unwrap();
-
-    // Check if backend is already running
-    if let Some(ref mut child) = *process {
-        match child.try_wait() {
-            Ok(None) => {
-                // Process is still running
-                info!("Backend process already running (PID: {}), skipping spawn", child.pid());
-                return Ok("Backend already running".to_string());
-            }
-            Ok(Some(status)) => {
-                info!("Previous backend exited with status: {:?}", status);
-                // Process exited, we'll spawn a new one
-            }
-            Err(e) => {
-                warn!("Error checking backend status: {}", e);
-                // Assume process is dead, spawn new one
-            }
-        }
+    let telemetry = launch_backend(&app_handle, port).await?;
+    // A successful manual start means the hands-on work is done
+    if backend_state.maintenance.swap(false, Ordering::Relaxed) {
+        info!("Maintenance mode ended by backend start");
+        emit_maintenance_mode(&app_handle, false);
     }
+    Ok(telemetry)
+}
 
-    // Runtime detection: try to create sidecar command to determine if we're bundled
-    match app_handle.shell().sidecar("sts-gui-backend") {
-        Ok(sidecar_cmd) => {
-            // Bundled mode (production) - sidecar exists
-            // This works for both debug and release builds
-            info!("Bundled mode: launching sidecar with --production flag");
+/// Turn maintenance mode on or off. While on, the watchdog stays quiet and `backend-crashed`
+/// carries `maintenance: true`, so nothing restarts a backend the user stopped on purpose.
+/// It stays on until turned off here or until the next successful `start_backend`.
+/// Changes are announced with `backend-maintenance-mode`.
+#[tauri::command]
+async fn set_maintenance_mode(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    if backend_state.maintenance.swap(enabled, Ordering::Relaxed) != enabled {
+        info!("Maintenance mode {}", if enabled { "enabled" } else { "disabled" });
+        emit_maintenance_mode(&app_handle, enabled);
+    }
+    Ok(())
+}
 
-            let workspace_dir = get_workspace_dir(&app_handle, true)?;
-            debug!("Using workspace directory: {:?}", workspace_dir);
+fn emit_maintenance_mode(app_handle: &AppHandle, enabled: bool) {
+    if let Err(e) = app_handle.emit("backend-maintenance-mode", enabled) {
+        warn!("Failed to emit backend-maintenance-mode event: {}", e);
+    }
+}
 
-            // Spawn sidecar with --production flag and port
-            // Python backend will use these flags to determine production mode and port
-            // NOTE: Tauri sidecars automatically get stdin piped (can use child.write())
-            // This enables stdin EOF monitoring for parent death detection
-            let (mut rx, sidecar_child) = sidecar_cmd
-                .args(["--production", "--port", &PROD_PORT.to_string()])
-                .spawn()
-                .map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
+/// Payload of the `backend-failed` event
+#[derive(Debug, Clone, Serialize)]
+struct BackendFailed {
+    message: String,
+}
 
-            let pid = sidecar_child.pid();
-            info!("Backend sidecar started with PID: {}", pid);
-            debug!("Arguments: [\"--production\", \"--port\", \"{}\"]", PROD_PORT);
+/// Event-driven variant of `start_backend`: returns immediately and reports progress via
+/// `backend-starting`, then `backend-ready` (with the same telemetry) or `backend-failed`.
+/// Crashes after readiness are reported through `backend-crashed` as usual.
+#[tauri::command]
+async fn start_backend_async(app_handle: AppHandle, port: Option<u32>) -> Result<(), String> {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = app_handle.emit("backend-starting", ()) {
+            warn!("Failed to emit backend-starting event: {}", e);
+        }
 
-            // Capture sidecar output for debugging - only in debug builds
-            #[cfg(debug_assertions)]
-            {
-                tauri::async_runtime::spawn(async move {
-                    while let Some(event) = rx.recv().await {
-                        match event {
-                            CommandEvent::Stdout(line) => {
-                                if let Ok(s) = String::from_utf8(line) {
-                                    debug!("[Backend stdout] {}", s);
-                                }
-                            }
-                            CommandEvent::Stderr(line) => {
-                                if let Ok(s) = String::from_utf8(line) {
-                                    warn!("[Backend stderr] {}", s);
-                                }
-                            }
-                            CommandEvent::Error(err) => {
-                                error!("[Backend error] {}", err);
-                            }
-                            CommandEvent::Terminated(payload) => {
-                                info!("[Backend terminated] {:?}", payload);
-                            }
-                            _ => {}
-                        }
-                    }
-                });
+        if let Err(message) = start_backend(app_handle.clone(), port).await {
+            error!("Backend failed to start: {}", message);
+            if let Err(e) = app_handle.emit("backend-failed", BackendFailed { message }) {
+                warn!("Failed to emit backend-failed event: {}", e);
             }
+        }
+    });
+    Ok(())
+}
 
-            // In release builds, drop the receiver to avoid memory buildup
-            #[cfg(not(debug_assertions))]
-            {
-                drop(rx);
-            }
+/// Restart the backend so a changed launch setting (`reason`, e.g. "the device") takes
+/// effect; nothing happens if no backend is running, since the next start picks it up.
+/// Returns whether a restart happened.
+async fn restart_if_running(app_handle: AppHandle, reason: &str) -> Result<bool, String> {
+    let running = app_handle.state::<BackendProcess>().child.lock().unwrap().is_some();
+    if !running {
+        debug!("Backend not running; {} applies from the next start", reason);
+        return Ok(false);
+    }
+    info!("Restarting backend to apply {}", reason);
+    restart_backend(app_handle, None).await?;
+    Ok(true)
+}
 
-            // CRITICAL: Store the sidecar process handle for lifecycle management
-            // Tauri does NOT automatically clean up sidecar processes on exit
-            // Lock is already held from the check above
-            *process = Some(BackendChild::Sidecar(sidecar_child));
-            info!("Sidecar backend stored in state for manual lifecycle management");
+/// Restart the backend, optionally on a different port
+#[tauri::command]
+async fn restart_backend(app_handle: AppHandle, port: Option<u32>) -> Result<StartupTelemetry, String> {
+    info!("Restarting FastAPI backend server");
 
-            Ok("Backend started successfully (production)".to_string())
-        }
-        Err(e) => {
-            // Development mode - sidecar doesn't exist
-            // This happens during `tauri dev`
-            info!("Development mode: expecting backend at localhost:8000");
-            debug!("Sidecar not found: {}", e);
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::Restarting)?;
 
-            let workspace_dir = get_workspace_dir(&app_handle, false)?;
-            debug!("Using workspace directory: {:?}", workspace_dir);
+    shutdown_backend(&app_handle);
+    launch_backend(&app_handle, port).await
+}
 
-            // Start the FastAPI backend using uv (dev mode uses port 8000)
-            // Python will independently determine the same workspace path
-            let mut child = Command::new("uv")
-                .args(&["run", "sts-gui-server", "--port", &DEV_PORT.to_string()])
-                .current_dir(&workspace_dir)
-                .stdin(Stdio::piped())  // CRITICAL: Pipe stdin for parent death detection
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .map_err(|e| format!("Failed to start backend from {:?}: {}", workspace_dir, e))?;
+/// How long `recover_backend` waits for the previous port to be released
+const PORT_RELEASE_WAIT: Duration = Duration::from_secs(1);
 
-            info!("Backend server started with PID: {} on port {}", child.id(), DEV_PORT);
+/// Restart for self-healing (after `backend-crashed` or `backend-unresponsive`), keeping
+/// the previous port so the frontend's cached base URL stays valid. Only if that port is
+/// still taken is a free one picked, announced with `backend-port-changed`.
+/// Refused while maintenance mode is on.
+#[tauri::command]
+async fn recover_backend(app_handle: AppHandle) -> Result<StartupTelemetry, String> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    if backend_state.maintenance.load(Ordering::Relaxed) {
+        return Err(BackendError::OperationInProgress(
+            "maintenance mode is on; turn it off to let the backend restart".to_string(),
+        )
+        .into());
+    }
+    let _operation = backend_state.begin_operation(BackendOperation::Restarting)?;
 
-            // Capture stdout/stderr in background threads - only in debug builds
-            #[cfg(debug_assertions)]
-            {
-                if let Some(stdout) = child.stdout.take() {
-                    std::thread::spawn(move || {
-                        let reader = BufReader::new(stdout);
-                        for line in reader.lines() {
-                            if let Ok(line) = line {
-                                debug!("[Backend stdout] {}", line);
-                            }
-                        }
-                    });
-                }
+    shutdown_backend(&app_handle);
+    let previous = *backend_state.last_port.lock().unwrap();
+    let port = match previous {
+        Some(previous) => {
+            let started = Instant::now();
+            // A killed backend can take a moment to release its socket
+            let mut free = port_is_free(previous);
+            while !free && started.elapsed() < PORT_RELEASE_WAIT {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                free = port_is_free(previous);
+            }
 
-                if let Some(stderr) = child.stderr.take() {
-                    std::thread::spawn(move || {
-                        let reader = BufReader::new(stderr);
-                        for line in reader.lines() {
-                            if let Ok(line) = line {
-                                warn!("[Backend stderr] {}", line);
-                            }
-                        }
-                    });
+            if free {
+                info!("Recovering backend on its previous port {}", previous);
+                Some(previous)
+            } else {
+                let fresh = processes::free_port().map_err(|e| format!("Failed to find a free port: {}", e))?;
+                warn!("Previous backend port {} is taken; recovering on port {}", previous, fresh);
+                let change = BackendPortChanged {
+                    from: previous,
+                    to: fresh,
+                    reason: "port_taken",
+                };
+                if let Err(e) = app_handle.emit("backend-port-changed", &change) {
+                    warn!("Failed to emit backend-port-changed event: {}", e);
                 }
+                Some(fresh)
             }
+        }
+        // Never been ready; fall back to the usual port resolution
+        None => None,
+    };
 
-            // In release builds, don't capture output to avoid overhead
-            #[cfg(not(debug_assertions))]
-            {
-                // Take and drop to prevent pipe blocking
-                child.stdout.take();
-                child.stderr.take();
-            }
+    let telemetry = launch_backend(&app_handle, port.map(u32::from)).await?;
+    // launch_backend only saw the fresh port; report the move from the previous one
+    if let Some(previous) = previous.filter(|previous| port != Some(*previous)) {
+        let mut resolution = backend_state.port_resolution.lock().unwrap();
+        if let Some(resolution) = resolution.as_mut() {
+            resolution.requested = previous;
+            resolution.fallback_used = true;
+            resolution.reason = Some("port_taken");
+        }
+    }
+    Ok(telemetry)
+}
 
-            let pid = child.id();
+fn port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind((health::BACKEND_HOST, port)).is_ok()
+}
 
-            // Store the dev process using the BackendChild enum
-            // Lock is already held from the check above
-            *process = Some(BackendChild::Dev(child));
-            info!("Dev backend stored in state for manual lifecycle management (PID: {})", pid);
+/// Refine a readiness failure: if the process is alive and its port is bound, the server
+/// is most likely up but blocked from us (firewall, endpoint security), not slow to start
+async fn diagnose_ready_failure(pid: u32, port: u16, error: BackendError) -> BackendError {
+    if !matches!(error, BackendError::Timeout(_)) {
+        return error;
+    }
 
-            Ok("Backend started successfully (development)".to_string())
-        }
+    let serving = tauri::async_runtime::spawn_blocking(move || {
+        processes::is_alive(pid) && processes::is_listening_on(pid, port)
+    })
+    .await
+    .unwrap_or(false);
+    if !serving {
+        return error;
     }
-}
 
-#[tauri::command]
-async fn stop_backend(app_handle: AppHandle) -> Result<String, String> {
-    info!("Stopping FastAPI backend server");
+    warn!("Backend (PID: {}) is listening on port {} but health checks never got through", pid, port);
+    BackendError::Unreachable(format!(
+        "the backend is running and listening on {}:{}, but connections to it are failing. \
+         A firewall or security software may be blocking local connections; allow the app \
+         or try a different port. ({})",
+        health::BACKEND_HOST,
+        port,
+        error
+    ))
+}
 
-    shutdown_backend(&app_handle);
+/// Payload of the `backend-port-changed` event
+#[derive(Debug, Clone, Serialize)]
+struct BackendPortChanged {
+    from: u16,
+    to: u16,
+    reason: &'static str,
+}
 
-    Ok("Backend stopped successfully".to_string())
+/// Result of `get_port_resolution`
+#[derive(Debug, Clone, Serialize)]
+struct PortResolution {
+    /// The port asked for (argument, `STS_PORT`, config or default, or the previous port
+    /// when recovering)
+    requested: u16,
+    actual: u16,
+    fallback_used: bool,
+    /// Why the port changed, as in `backend-port-changed`
+    reason: Option<&'static str>,
 }
 
-#[tauri::command]
-async fn get_workspace_path(app_handle: AppHandle) -> Result<String, String> {
-    // Check if sidecar exists to determine bundled mode
-    let is_bundled = app_handle.shell().sidecar("sts-gui-backend").is_ok();
+/// Spawn the backend (if needed) and wait until its health endpoint answers.
+/// If it failed because another process took the port first, it is retried once on a
+/// fresh port, announced with `backend-port-changed`.
+async fn launch_backend(app_handle: &AppHandle, port: Option<u32>) -> Result<StartupTelemetry, String> {
+    // Validate before spawning so a bad setting doesn't leave a backend we can't probe
+    let health_path = health::resolve_health_path(&config::current(app_handle))?;
+    let probe_settings = health::resolve_probe_settings(&config::current(app_handle))?;
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let (abort_sender, mut abort_receiver) = tokio::sync::oneshot::channel();
+    *backend_state.startup_abort.lock().unwrap() = Some(abort_sender);
+
+    let mut port = port;
+    let mut retried = false;
+    let mut requested = None;
+    let ready = loop {
+        let spawned = match spawn_backend(app_handle, port) {
+            Ok(spawned) => spawned,
+            Err(e) => break Ok(Err(e)),
+        };
+        requested.get_or_insert(spawned.port);
+        let wait = health::wait_for_backend_ready(
+            spawned.port,
+            &health_path,
+            probe_settings,
+            health::READY_TIMEOUT,
+        );
+        let result = tokio::select! {
+            result = wait => result,
+            Ok(()) = &mut abort_receiver => break Err(spawned.pid),
+        };
+
+        match result {
+            Ok(_) => break Ok(Ok(spawned)),
+            // Something grabbed the port between our check and the backend's bind;
+            // move once to a port that is free now rather than failing the start
+            Err(BackendError::Timeout(_))
+                if !retried && app_handle.state::<BackendStderr>().has_bind_conflict(spawned.port) =>
+            {
+                retried = true;
+                let fresh = match processes::free_port() {
+                    Ok(fresh) => fresh,
+                    Err(e) => break Ok(Err(format!("Failed to find a free port: {}", e))),
+                };
+                warn!(
+                    "Port {} was taken before the backend could bind it; retrying on port {}",
+                    spawned.port, fresh
+                );
+                shutdown_backend(app_handle);
+                let change = BackendPortChanged {
+                    from: spawned.port,
+                    to: fresh,
+                    reason: "port_conflict",
+                };
+                if let Err(e) = app_handle.emit("backend-port-changed", &change) {
+                    warn!("Failed to emit backend-port-changed event: {}", e);
+                }
+                port = Some(u32::from(fresh));
+            }
+            Err(e) => break Ok(Err(diagnose_ready_failure(spawned.pid, spawned.port, e).await.into())),
+        }
+    };
+    backend_state.startup_abort.lock().unwrap().take();
+
+    let spawned = match ready {
+        Ok(result) => result?,
+        Err(pid) => {
+            warn!("Backend startup aborted by user (PID: {})", pid);
+            shutdown_backend(app_handle);
+            if let Err(e) = app_handle.emit("backend-startup-aborted", pid) {
+                warn!("Failed to emit backend-startup-aborted event: {}", e);
+            }
+            return Err("Backend startup was aborted".to_string());
+        }
+    };
+    mark_backend_ready(app_handle, spawned.pid);
+    *backend_state.last_port.lock().unwrap() = Some(spawned.port);
+    let requested = requested.unwrap_or(spawned.port);
+    *backend_state.port_resolution.lock().unwrap() = Some(PortResolution {
+        requested,
+        actual: spawned.port,
+        fallback_used: requested != spawned.port,
+        reason: (requested != spawned.port).then_some("port_conflict"),
+    });
+
+    let telemetry = StartupTelemetry {
+        mode: spawned.mode,
+        pid: spawned.pid,
+        port: spawned.port,
+        startup_ms: spawned.spawned_at.elapsed().as_millis() as u64,
+        already_running: spawned.already_running,
+    };
+    info!("Backend startup telemetry: {:?}", telemetry);
+    if !telemetry.already_running {
+        app_handle
+            .state::<StartupHistory>()
+            .record(app_handle, telemetry.mode.as_str(), telemetry.port, telemetry.startup_ms);
+    }
+
+    if let Err(e) = app_handle.emit("backend-ready", &telemetry) {
+        warn!("Failed to emit backend-ready event: {}", e);
+    }
+
+    if !spawned.already_running {
+        match health::resolve_watchdog_settings(&config::current(app_handle)) {
+            Ok(settings) => {
+                let app = app_handle.clone();
+                let (pid, port) = (spawned.pid, spawned.port);
+                tauri::async_runtime::spawn(async move {
+                    run_watchdog(app, pid, port, health_path, settings).await;
+                });
+            }
+            Err(e) => warn!("Backend watchdog disabled: {}", e),
+        }
+    }
+
+    // Fire-and-forget: warming up must never delay or fail startup
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = prewarm_backend(app).await {
+            warn!("Backend prewarm failed: {}", e);
+        }
+    });
+
+    if !spawned.already_running {
+        let app = app_handle.clone();
+        let (pid, port) = (spawned.pid, spawned.port);
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = fetch_audio_config(&app, pid, port).await {
+                debug!("Backend audio settings not cached: {}", e);
+            }
+            if let Err(e) = get_backend_capabilities(app).await {
+                debug!("Backend capabilities not cached: {}", e);
+            }
+        });
+    }
+
+    Ok(telemetry)
+}
+
+/// Payload of the `backend-unresponsive` event
+#[derive(Debug, Clone, Serialize)]
+struct BackendUnresponsive {
+    pid: u32,
+    port: u16,
+    consecutive_failures: u32,
+    last_error: String,
+}
+
+/// Probe a running backend periodically and emit `backend-unresponsive` once it has failed
+/// `failure_threshold` consecutive health checks while its process is still alive.
+/// Complements crash detection, which only sees the process exit. Fires once per episode;
+/// a successful probe re-arms it. Ends when `pid` is no longer the tracked backend
+/// (stopped, restarted or crashed).
+async fn run_watchdog(
+    app_handle: AppHandle,
+    pid: u32,
+    port: u16,
+    health_path: String,
+    settings: health::WatchdogSettings,
+) {
+    debug!("Watchdog started for backend PID {} ({:?})", pid, settings);
+    let mut failures = 0;
+
+    loop {
+        tokio::time::sleep(settings.interval).await;
+
+        let (tracked, busy) = {
+            let backend_state: State<BackendProcess> = app_handle.state();
+            let process = backend_state.child.lock().unwrap();
+            let running = process.as_ref().filter(|running| running.child.pid() == pid);
+            // A quiesced backend has stopped answering on purpose, and in maintenance
+            // mode the user may be poking at it; neither should trigger recovery
+            let busy = backend_state.operation.lock().unwrap().is_some()
+                || backend_state.maintenance.load(Ordering::Relaxed)
+                || running.is_some_and(|running| running.quiesced);
+            (running.is_some(), busy)
+        };
+        if !tracked {
+            debug!("Watchdog for backend PID {} stopping", pid);
+            return;
+        }
+        // Lifecycle operations legitimately make the backend unreachable for a while
+        if busy {
+            continue;
+        }
+
+        match health::probe(port, &health_path).await {
+            Ok(_) => failures = 0,
+            Err(e) => {
+                failures += 1;
+                debug!("Watchdog probe {} failed for PID {}: {}", failures, pid, e);
+                if failures == settings.failure_threshold && processes::is_alive(pid) {
+                    warn!("Backend PID {} is alive but unresponsive: {}", pid, e);
+                    let payload = BackendUnresponsive {
+                        pid,
+                        port,
+                        consecutive_failures: failures,
+                        last_error: e,
+                    };
+                    if let Err(e) = app_handle.emit("backend-unresponsive", &payload) {
+                        warn!("Failed to emit backend-unresponsive event: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Record readiness, unless the backend was replaced while we were waiting
+fn mark_backend_ready(app_handle: &AppHandle, pid: u32) {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let mut process = backend_state.child.lock().unwrap();
+    if let Some(running) = process.as_mut().filter(|running| running.child.pid() == pid) {
+        running.ready = true;
+    }
+    drop(process);
+    backend_state.notify_state_changed();
+}
+
+/// Spawn the backend unless one is already running.
+/// Callers are responsible for holding an `OperationGuard`.
+fn spawn_backend(app_handle: &AppHandle, port: Option<u32>) -> Result<SpawnOutcome, String> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+
+    // Hold lock through check and spawn to prevent race condition
+    // If two threads call start_backend simultaneously, only one will spawn
+    let mut process = backend_state.child.lock().unwrap();
+
+    // Check if backend is already running
+    if let Some(ref mut running) = *process {
+        match running.child.try_wait() {
+            Ok(None) => {
+                // Process is still running
+                info!("Backend process already running (PID: {}), skipping spawn", running.child.pid());
+                return Ok(SpawnOutcome {
+                    mode: running.mode,
+                    pid: running.child.pid(),
+                    port: running.port,
+                    spawned_at: Instant::now(),
+                    already_running: true,
+                });
+            }
+            Ok(Some(status)) => {
+                info!("Previous backend exited with status: {:?}", status);
+                // Process exited, we'll spawn a new one
+            }
+            Err(e) => {
+                warn!("Error checking backend status: {}", e);
+                // Assume process is dead, spawn new one
+            }
+        }
+    }
+
+    let (mode, mode_source) = resolve_backend_mode(app_handle)?;
+    info!("Backend mode: {} (from {})", mode.as_str(), mode_source);
+
+    let workspace_dir = get_workspace_dir(app_handle, mode.is_bundled())?;
+    debug!("Using workspace directory: {:?}", workspace_dir);
+    // The backend also creates these, but doing it here makes first run work even if it can't
+    if let Err(e) = workspace::ensure_structure(&workspace_dir) {
+        warn!("{}", e);
+    }
+    // Extra flags from settings. The backend drops flags it doesn't declare (parse_known_args),
+    // so the commands that set them first check the running backend supports them.
+    let settings = config::current(app_handle);
+    let mut extra_args: Vec<String> = Vec::new();
+    if let Some(workers) = settings.workers {
+        extra_args.extend(["--workers".to_string(), workers.to_string()]);
+    }
+    if let Some(profile) = &settings.profile {
+        extra_args.extend(["--profile".to_string(), profile.clone()]);
+    }
+    if let Some(device) = &settings.device {
+        extra_args.extend(["--device".to_string(), device.clone()]);
+    }
+    match settings.output_dir.as_deref().map(Path::new) {
+        Some(dir) if dir.is_dir() => {
+            extra_args.extend(["--output-dir".to_string(), dir.to_string_lossy().into_owned()]);
+        }
+        // Typically an external drive that is not mounted; writing into the workspace beats failing every job
+        Some(dir) => warn!("Output directory {:?} is unavailable; using the workspace output folder", dir),
+        None => {}
+    }
+
+    // Only pass the location when it was moved; otherwise the backend derives the same default
+    let mut backend_env: Vec<(&str, String)> = match config::current(app_handle).workspace_dir {
+        Some(_) => vec![(
+            workspace::BACKEND_WORKSPACE_ENV_VAR,
+            workspace_dir.to_string_lossy().into_owned(),
+        )],
+        None => Vec::new(),
+    };
+    // API keys from the OS keychain, so they never need to sit in plaintext config
+    let secret_env = secrets::backend_env();
+    let mut secret_names: Vec<&str> = secret_env.iter().map(|(name, _)| *name).collect();
+    backend_env.extend(secret_env);
+    // Proxy for provider calls; the URL may carry credentials, so it's redacted like a key
+    if let Some(proxy_url) = &settings.proxy_url {
+        for name in PROXY_ENV_VARS {
+            backend_env.push((name, proxy_url.clone()));
+            secret_names.push(name);
+        }
+        backend_env.push(("NO_PROXY", NO_PROXY_HOSTS.to_string()));
+        backend_env.push(("no_proxy", NO_PROXY_HOSTS.to_string()));
+    }
+
+    app_handle.state::<BackendStderr>().mark_spawn();
+    match mode {
+        BackendMode::Production => {
+            // Bundled mode (production) - sidecar exists
+            // This works for both debug and release builds
+            let sidecar_cmd = app_handle
+                .shell()
+                .sidecar("sts-gui-backend")
+                .map_err(|e| format!("Production mode requested but sidecar is unavailable: {}", e))?;
+            info!("Bundled mode: launching sidecar with --production flag");
+
+            let port = port::resolve_backend_port(port, &config::current(app_handle), PROD_PORT)?;
+
+            // Spawn sidecar with --production flag and port
+            // Python backend will use these flags to determine production mode and port
+            // NOTE: Tauri sidecars automatically get stdin piped (can use child.write())
+            // This enables stdin EOF monitoring for parent death detection, unless the
+            // user turned it off with `set_parent_death_shutdown`. Only `--production`
+            // backends run the monitor, so dev launches don't get the flag.
+            let parent_death_shutdown = config::current(app_handle).parent_death_shutdown.unwrap_or(true);
+            let args: Vec<String> = ["--production".to_string(), "--port".to_string(), port.to_string()]
+                .into_iter()
+                .chain((!parent_death_shutdown).then(|| "--ignore-stdin".to_string()))
+                .chain(extra_args)
+                .collect();
+            let program = sidecar_path(app_handle).unwrap_or_else(|| PathBuf::from("sts-gui-backend"));
+            let launch_command = LaunchCommand::new(
+                program.to_string_lossy().into_owned(),
+                &args,
+                None,
+                &backend_env,
+                &secret_names,
+            );
+
+            let spawned_at = Instant::now();
+            let (mut rx, sidecar_child) = sidecar_cmd
+                .args(&args)
+                .envs(backend_env)
+                .spawn()
+                .map_err(|e| {
+                    let error = e.to_string();
+                    quarantine::explain_spawn_failure(&program, &error)
+                        .or_else(|| architecture::explain_spawn_failure(&program, &error))
+                        .unwrap_or_else(|| format!("Failed to spawn sidecar: {}", error))
+                })?;
+
+            let pid = sidecar_child.pid();
+            info!("Backend sidecar started with PID: {}", pid);
+            let resource_limits = limits::ResourceLimits::from_config(&config::current(app_handle));
+            if let Err(e) = limits::apply_to_process(pid, resource_limits) {
+                warn!("Backend resource limits not applied: {}", e);
+            }
+            debug!("Arguments: {:?}", launch_command.args);
+
+            // Always drain the event channel: it is bounded, so an unread receiver would
+            // eventually block the plugin's pipe readers and stall the backend's writes.
+            // The pipeline decides what to keep per build; termination is handled here.
+            let app = app_handle.clone();
+            let source = log_source(BackendMode::Production, port);
+            tauri::async_runtime::spawn(async move {
+                let pipeline = app.state::<LogPipeline>();
+                while let Some(event) = rx.recv().await {
+                    match event {
+                        CommandEvent::Stdout(line) => {
+                            if let Ok(s) = String::from_utf8(line) {
+                                pipeline.send(&source, LogStream::Stdout, s);
+                            }
+                        }
+                        CommandEvent::Stderr(line) => {
+                            if let Ok(s) = String::from_utf8(line) {
+                                pipeline.send(&source, LogStream::Stderr, s);
+                            }
+                        }
+                        CommandEvent::Error(err) => {
+                            error!("[Backend error] {}", err);
+                        }
+                        CommandEvent::Terminated(payload) => {
+                            info!("[Backend terminated] {:?}", payload);
+                            handle_backend_exit(&app, pid, payload.code, payload.signal);
+                        }
+                        _ => {}
+                    }
+                }
+            });
+
+            // CRITICAL: Store the sidecar process handle for lifecycle management
+            // Tauri does NOT automatically clean up sidecar processes on exit
+            // Lock is already held from the check above
+            *process = Some(RunningBackend {
+                child: BackendChild::Sidecar(sidecar_child),
+                mode: BackendMode::Production,
+                port,
+                started_at: spawned_at,
+                ready: false,
+                capabilities: None,
+                audio_config: None,
+                profile: settings.profile.clone(),
+                workers: settings.workers,
+                launch_command,
+                quiesced: false,
+                resource_limits,
+            });
+            info!("Sidecar backend stored in state for manual lifecycle management");
+            backend_state.notify_state_changed();
+
+            Ok(SpawnOutcome {
+                mode: BackendMode::Production,
+                pid,
+                port,
+                spawned_at,
+                already_running: false,
+            })
+        }
+        BackendMode::Development => {
+            // Development mode - sidecar doesn't exist (or dev was forced)
+            // This happens during `tauri dev`
+
+            let port = port::resolve_backend_port(port, &config::current(app_handle), DEV_PORT)?;
+            info!("Development mode: expecting backend at localhost:{}", port);
+
+            // Start the FastAPI backend, by default via uv (dev mode defaults to port 8000)
+            // Python will independently determine the same workspace path unless it was moved
+            // `STS_DEV_BACKEND_CMD` can swap uv for another runner or a debugger wrapper
+            let (program, mut args) =
+                dev_backend::resolve_command(port, &workspace_dir.to_string_lossy())?;
+            args.extend(extra_args);
+            let launch_command = LaunchCommand::new(
+                program.clone(),
+                &args,
+                Some(env!("DEV_WORKSPACE_ROOT")),
+                &backend_env,
+                &secret_names,
+            );
+
+            let resource_limits = limits::ResourceLimits::from_config(&config::current(app_handle));
+            let mut command = Command::new(&program);
+            command
+                .args(&args)
+                // uv needs the project root, which stays put even if the workspace moved
+                .current_dir(env!("DEV_WORKSPACE_ROOT"))
+                .envs(backend_env)
+                .stdin(Stdio::piped())  // CRITICAL: Pipe stdin for parent death detection
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            #[cfg(unix)]
+            limits::apply_before_exec(&mut command, resource_limits);
+
+            let spawned_at = Instant::now();
+            let mut child = command.spawn().map_err(|e| {
+                format!("Failed to start backend ({}) from {:?}: {}", program, env!("DEV_WORKSPACE_ROOT"), e)
+            })?;
+
+            info!("Backend server started with PID: {} on port {}", child.id(), port);
+            #[cfg(windows)]
+            if let Err(e) = limits::apply_to_process(child.id(), resource_limits) {
+                warn!("Backend resource limits not applied: {}", e);
+            }
+
+            // Forward stdout to the log pipeline; it is only logged in debug builds,
+            // but job progress markers are picked out of it in every build
+            let source = log_source(BackendMode::Development, port);
+            if let Some(stdout) = child.stdout.take() {
+                let app = app_handle.clone();
+                let source = source.clone();
+                std::thread::spawn(move || {
+                    let pipeline = app.state::<LogPipeline>();
+                    let reader = BufReader::new(stdout);
+                    for line in reader.lines().map_while(Result::ok) {
+                        pipeline.send(&source, LogStream::Stdout, line);
+                    }
+                });
+            }
+
+            let pid = child.id();
+
+            // Always read stderr so the last error/traceback is available after a crash.
+            // EOF on stderr means the process is exiting, which doubles as crash detection.
+            if let Some(stderr) = child.stderr.take() {
+                let app = app_handle.clone();
+                std::thread::spawn(move || {
+                    let pipeline = app.state::<LogPipeline>();
+                    let reader = BufReader::new(stderr);
+                    for line in reader.lines().map_while(Result::ok) {
+                        pipeline.send(&source, LogStream::Stderr, line);
+                    }
+                    reap_dev_backend(&app, pid);
+                });
+            }
+
+            // Store the dev process using the BackendChild enum
+            // Lock is already held from the check above
+            *process = Some(RunningBackend {
+                child: BackendChild::Dev(child),
+                mode: BackendMode::Development,
+                port,
+                started_at: spawned_at,
+                ready: false,
+                capabilities: None,
+                audio_config: None,
+                profile: settings.profile.clone(),
+                workers: settings.workers,
+                launch_command,
+                quiesced: false,
+                resource_limits,
+            });
+            info!("Dev backend stored in state for manual lifecycle management (PID: {})", pid);
+            backend_state.notify_state_changed();
+
+            Ok(SpawnOutcome {
+                mode: BackendMode::Development,
+                pid,
+                port,
+                spawned_at,
+                already_running: false,
+            })
+        }
+    }
+}
+
+#[tauri::command]
+async fn stop_backend(app_handle: AppHandle) -> Result<String, String> {
+    info!("Stopping FastAPI backend server");
+
+    shutdown_backend(&app_handle);
+
+    Ok("Backend stopped successfully".to_string())
+}
+
+/// Stop the backend and wait until its processes are confirmed gone, for the updater
+/// to call before swapping binaries. Fails listing every PID that outlives the timeout.
+#[tauri::command]
+async fn prepare_for_update(app_handle: AppHandle, timeout_ms: Option<u64>) -> Result<(), String> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_UPDATE_STOP_TIMEOUT_MS));
+    info!("Preparing for update: stopping backend (timeout {:?})", timeout);
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::Stopping)?;
+
+    let pids = stop_backend_and_wait(&app_handle, timeout).await?;
+    if !pids.is_empty() {
+        info!("Backend (PIDs: {:?}) confirmed stopped; safe to update", pids);
+    }
+    Ok(())
+}
+
+/// Stop every tracked backend and wait until all their processes are gone, returning the
+/// PIDs that were running. Callers are responsible for holding an `OperationGuard`.
+async fn stop_backend_and_wait(app_handle: &AppHandle, timeout: Duration) -> Result<Vec<u32>, String> {
+    let pids = shutdown_backend(app_handle);
+
+    let started = Instant::now();
+    loop {
+        let alive: Vec<u32> = pids.iter().copied().filter(|pid| processes::is_alive(*pid)).collect();
+        if alive.is_empty() {
+            return Ok(pids);
+        }
+        if started.elapsed() >= timeout {
+            error!("Backend (PIDs: {:?}) still running {:?} after kill", alive, timeout);
+            return Err(format!(
+                "Backend processes could not be stopped within {:?} (PIDs: {})",
+                timeout,
+                alive.iter().map(u32::to_string).collect::<Vec<_>>().join(", ")
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// End a session cleanly: stop the backend, wait for its process to exit and its last
+/// output to be logged, then move the app's log files into `<workspace>/logs/<UTC time>-<label>/`.
+/// Returns the archive folder and the files in it. Logging continues in a fresh file.
+#[tauri::command]
+async fn stop_and_archive_logs(app_handle: AppHandle, label: Option<String>) -> Result<logs::LogArchive, String> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::Stopping)?;
+
+    let pids = stop_backend_and_wait(&app_handle, Duration::from_millis(DEFAULT_UPDATE_STOP_TIMEOUT_MS)).await?;
+    if !pids.is_empty() {
+        info!("Backend (PIDs: {:?}) stopped; archiving logs", pids);
+    }
+    // The pipe readers may still be handing over the last lines after the process is gone
+    app_handle.state::<LogPipeline>().flush_async().await;
+
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let archive_root = get_workspace_dir(&app_handle, mode.is_bundled())?.join(LOG_ARCHIVE_DIR_NAME);
+    let log_dir = app_handle
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log directory: {}", e))?;
+
+    let archive = tauri::async_runtime::spawn_blocking(move || logs::archive(&log_dir, &archive_root, label.as_deref()))
+        .await
+        .map_err(|e| format!("Log archive task failed: {}", e))??;
+    Ok(archive)
+}
+
+/// Restart the whole app, for settings that only apply at launch (CSP, window config).
+/// The backend is stopped and confirmed gone first so the new instance can bind its port.
+#[tauri::command]
+async fn relaunch_app(app_handle: AppHandle) -> Result<(), String> {
+    info!("Relaunching app");
+    {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let _operation = backend_state.begin_operation(BackendOperation::Stopping)?;
+        let timeout = Duration::from_millis(DEFAULT_UPDATE_STOP_TIMEOUT_MS);
+        let pids = stop_backend_and_wait(&app_handle, timeout).await?;
+        if !pids.is_empty() {
+            info!("Backend (PIDs: {:?}) stopped before relaunch", pids);
+        }
+    }
+
+    app_handle.restart()
+}
+
+#[tauri::command]
+async fn get_workspace_path(app_handle: AppHandle) -> Result<String, String> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled())?;
+    workspace_dir
+        .to_str()
+        .ok_or_else(|| "Failed to convert workspace path to string".to_string())
+        .map(|s| s.to_string())
+}
+
+/// Workspace location details. Emits `workspace-sync-warning` when the workspace looks like
+/// a Dropbox, iCloud Drive, OneDrive or Google Drive folder; also checked at startup.
+#[tauri::command]
+async fn get_workspace_info(app_handle: AppHandle) -> Result<workspace::WorkspaceInfo, String> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled())?;
+    let info = workspace::info(&workspace_dir);
+    if info.sync_provider.is_some() {
+        if let Err(e) = app_handle.emit("workspace-sync-warning", &info) {
+            warn!("Failed to emit workspace-sync-warning event: {}", e);
+        }
+    }
+    Ok(info)
+}
+
+/// Create any missing workspace subdirectories ("repair workspace"), returning the ones created
+#[tauri::command]
+async fn ensure_workspace_structure(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled())?;
+    workspace::ensure_structure(&workspace_dir)
+}
+
+/// Free space on the workspace volume, so the UI can warn before a large job
+#[tauri::command]
+async fn get_workspace_free_space(app_handle: AppHandle) -> Result<workspace::DiskSpace, String> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled())?;
+    tauri::async_runtime::spawn_blocking(move || workspace::free_space(&workspace_dir))
+        .await
+        .map_err(|e| format!("Disk space check failed: {}", e))?
+}
+
+/// Storage preflight: write, read back and delete a scratch file in the workspace
+#[tauri::command]
+async fn test_workspace_write(app_handle: AppHandle) -> Result<workspace::WriteTest, BackendError> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled()).map_err(BackendError::Io)?;
+    tauri::async_runtime::spawn_blocking(move || workspace::test_write(&workspace_dir))
+        .await
+        .map_err(|e| BackendError::Io(format!("workspace write test failed: {}", e)))?
+}
+
+/// Permission preflight: whether the app may write its workspace and data folder and reach
+/// its backend over loopback, with a remediation hint for each failure. Also run once at
+/// startup, which emits `permissions-problem` if anything fails.
+#[tauri::command]
+async fn check_permissions(app_handle: AppHandle) -> Result<permissions::PermissionReport, BackendError> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled()).map_err(BackendError::Io)?;
+    // The folder the config file is saved in; failing to locate it is a failed check, not an error
+    let app_data_dir = config::config_path(&app_handle).and_then(|path| {
+        path.parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("Config path {:?} has no parent folder", path))
+    });
+
+    tauri::async_runtime::spawn_blocking(move || permissions::check(&workspace_dir, app_data_dir))
+        .await
+        .map_err(|e| BackendError::Io(format!("permission check failed: {}", e)))
+}
+
+/// Run an allowlisted CLI tool (see `tools::ALLOWED_SUBCOMMANDS`) and return its output.
+/// The bundled sidecar only contains the GUI server, so this is dev-mode only for now.
+#[tauri::command]
+async fn run_backend_command(app_handle: AppHandle, args: Vec<String>) -> Result<tools::CommandOutput, String> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    if mode.is_bundled() {
+        return Err("Backend tools are not available in the bundled app".to_string());
+    }
+    tools::run(env!("DEV_WORKSPACE_ROOT"), &args).await
+}
+
+/// Configured backend worker count; `None` means the backend's own default
+#[tauri::command]
+async fn get_backend_workers(app_handle: AppHandle) -> Result<Option<u32>, String> {
+    Ok(config::current(&app_handle).workers)
+}
+
+/// Persist the backend worker count (`None` to reset) and restart a running backend so it
+/// takes effect. Limited to the CPU count and `MAX_BACKEND_WORKERS`. Setting a count needs a
+/// running backend that reports the `workers` feature; otherwise this fails with `Unsupported`
+/// and nothing is saved or restarted.
+#[tauri::command]
+async fn set_backend_workers(app_handle: AppHandle, workers: Option<u32>) -> Result<(), String> {
+    if let Some(workers) = workers {
+        require_backend_feature(&app_handle, "workers", "--workers").await?;
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get() as u32);
+        let max = cpus.min(MAX_BACKEND_WORKERS);
+        if workers == 0 || workers > max {
+            return Err(BackendError::InvalidArgument(format!(
+                "worker count {} must be between 1 and {} on this machine",
+                workers, max
+            ))
+            .into());
+        }
+    }
+
+    config::update(&app_handle, |config| config.workers = workers)?;
+    info!("Backend workers set to {:?}", workers);
+
+    restart_if_running(app_handle, "the worker count").await?;
+    Ok(())
+}
+
+/// Check the config file on disk and, if it is corrupt, back it up and rewrite it from the
+/// settings currently in effect (defaults, if it was already corrupt at startup). Emits
+/// `config-repaired` and returns the repair when one was needed.
+#[tauri::command]
+async fn repair_config(app_handle: AppHandle) -> Result<Option<config::ConfigRepair>, String> {
+    let repair = config::repair(&app_handle)?;
+    if let Some(repair) = &repair {
+        if let Err(e) = app_handle.emit("config-repaired", repair) {
+            warn!("Failed to emit config-repaired event: {}", e);
+        }
+    }
+    Ok(repair)
+}
+
+/// Developer watch mode: while enabled, saving the backend's `.env` file (in the folder the
+/// backend is started from) restarts a running backend once the edits settle. Emits
+/// `config-watch-restarting` before and `config-watch-restarted` after each restart.
+/// Off by default and not persisted. Returns the watched path while enabled.
+#[tauri::command]
+async fn set_config_watch(app_handle: AppHandle, enabled: bool) -> Result<Option<String>, BackendError> {
+    let watch_state = app_handle.state::<ConfigWatchState>();
+    if !enabled {
+        if watch_state.0.lock().unwrap().take().is_some() {
+            info!("Config watch turned off");
+        }
+        return Ok(None);
+    }
+
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let working_dir = match mode {
+        BackendMode::Development => PathBuf::from(env!("DEV_WORKSPACE_ROOT")),
+        BackendMode::Production => std::env::current_dir()
+            .map_err(|e| BackendError::Io(format!("failed to get working directory: {}", e)))?,
+    };
+    let path = config_watch::backend_env_file(&working_dir);
+    let (watcher, mut changes) = config_watch::watch(&path)?;
+    // Replacing a previous watcher closes its channel, which ends its task below
+    *watch_state.0.lock().unwrap() = Some(watcher);
+
+    let app = app_handle.clone();
+    let watched = path.display().to_string();
+    tauri::async_runtime::spawn(async move {
+        while config_watch::settled(&mut changes).await {
+            let running = app.state::<BackendProcess>().child.lock().unwrap().is_some();
+            if !running {
+                debug!("{} changed, but the backend is not running", watched);
+                continue;
+            }
+
+            info!("{} changed; restarting the backend", watched);
+            let restarting = ConfigWatchRestart {
+                path: watched.clone(),
+                error: None,
+            };
+            if let Err(e) = app.emit("config-watch-restarting", &restarting) {
+                warn!("Failed to emit config-watch-restarting event: {}", e);
+            }
+            let restarted = ConfigWatchRestart {
+                path: watched.clone(),
+                error: restart_backend(app.clone(), None).await.err(),
+            };
+            if let Err(e) = app.emit("config-watch-restarted", &restarted) {
+                warn!("Failed to emit config-watch-restarted event: {}", e);
+            }
+        }
+        debug!("Config watch for {} stopped", watched);
+    });
+    Ok(Some(path.display().to_string()))
+}
+
+/// Choose whether a running backend is restarted when the machine wakes (default: off).
+/// A backend that slept is often left unresponsive; `system-resumed` reports what was done.
+/// Wakes are detected the same way on every platform (see `power::watch_for_resume`), but
+/// only afterwards: nothing is notified before a suspend, so there is no stop-on-suspend
+/// option. Persisted.
+#[tauri::command]
+async fn set_power_handling(app_handle: AppHandle, restart_on_resume: bool) -> Result<(), String> {
+    config::update(&app_handle, |config| config.restart_on_resume = Some(restart_on_resume))?;
+    info!("Power handling set: restart on resume {}", restart_on_resume);
+    Ok(())
+}
+
+/// Persist caps on the backend's memory (MB) and CPU time (seconds), `None` for unlimited
+/// (the default), and restart a running backend so they take effect. A backend the OS stops
+/// at a cap emits `backend-resource-limit` next to `backend-crashed`. Enforcement differs
+/// per platform; see `limits::ResourceLimits`.
+#[tauri::command]
+async fn set_backend_resource_limits(
+    app_handle: AppHandle,
+    max_memory_mb: Option<u64>,
+    max_cpu_seconds: Option<u64>,
+) -> Result<(), String> {
+    let resource_limits = limits::ResourceLimits {
+        max_memory_mb,
+        max_cpu_seconds,
+    };
+    resource_limits.validate()?;
+
+    config::update(&app_handle, |config| {
+        config.backend_max_memory_mb = max_memory_mb;
+        config.backend_max_cpu_seconds = max_cpu_seconds;
+    })?;
+    info!("Backend resource limits set to {:?}", resource_limits);
+
+    restart_if_running(app_handle, "the resource limits").await?;
+    Ok(())
+}
+
+/// Configured compute device for the backend's local models, and whether a GPU was found
+#[tauri::command]
+async fn get_backend_device(app_handle: AppHandle) -> Result<device::DeviceInfo, String> {
+    Ok(device::DeviceInfo {
+        device: config::current(&app_handle).device.unwrap_or_else(|| "auto".to_string()),
+        gpu_detected: device::gpu_detected(),
+    })
+}
+
+/// Persist the backend's compute device (`cpu`, `gpu` or `auto`), passed as `--device`, and
+/// restart a running backend so it takes effect. `auto` clears the setting and leaves the
+/// choice to the backend. `gpu` is refused when this machine clearly has no usable GPU.
+/// `cpu` and `gpu` need a running backend that reports the `device` feature; otherwise this
+/// fails with `Unsupported` and nothing is saved or restarted.
+#[tauri::command]
+async fn set_backend_device(app_handle: AppHandle, device: String) -> Result<(), String> {
+    let device = device::validate(&device)?;
+    let configured = (device != "auto").then_some(device);
+    if configured.is_some() {
+        require_backend_feature(&app_handle, "device", "--device").await?;
+    }
+
+    config::update(&app_handle, |config| config.device = configured.clone())?;
+    info!("Backend device set to {}", configured.as_deref().unwrap_or("auto"));
+
+    restart_if_running(app_handle, "the device").await?;
+    Ok(())
+}
+
+/// Persist where the backend writes finished audiobooks, passed as `--output-dir`, and restart
+/// a running backend so it takes effect; `None` moves them back into `<workspace>/output`.
+/// Each audiobook goes to `<dir>/<project>/<project>.mp3`. The folder must be absolute, exist
+/// and pass a write test. Config, caches and generation logs stay in the workspace either way.
+#[tauri::command]
+async fn set_output_dir(app_handle: AppHandle, path: Option<String>) -> Result<(), String> {
+    let output_dir = match path.as_deref().map(str::trim).filter(|path| !path.is_empty()) {
+        Some(path) => {
+            let dir = PathBuf::from(path);
+            if !dir.is_absolute() {
+                return Err(BackendError::InvalidArgument(format!("output directory {:?} must be absolute", dir)).into());
+            }
+            if !dir.is_dir() {
+                return Err(BackendError::NotFound(format!("output directory {:?} does not exist", dir)).into());
+            }
+            let probe = dir.clone();
+            tauri::async_runtime::spawn_blocking(move || workspace::test_write(&probe))
+                .await
+                .map_err(|e| BackendError::Io(e.to_string()))??;
+            Some(dir.to_string_lossy().into_owned())
+        }
+        None => None,
+    };
+
+    config::update(&app_handle, |config| config.output_dir = output_dir.clone())?;
+    info!("Backend output directory set to {}", output_dir.as_deref().unwrap_or("the workspace"));
+
+    restart_if_running(app_handle, "the output directory").await?;
+    Ok(())
+}
+
+/// Check a selected script file (existence, type, size, readability) without a backend round trip
+#[tauri::command]
+async fn validate_script_path(app_handle: AppHandle, path: String) -> Result<scripts::ScriptValidation, String> {
+    let extensions = script_extensions(&app_handle);
+    tauri::async_runtime::spawn_blocking(move || scripts::validate(Path::new(&path), &extensions))
+        .await
+        .map_err(|e| format!("Script validation failed: {}", e))
+}
+
+/// Script extensions accepted from the config, or the defaults
+fn script_extensions(app_handle: &AppHandle) -> Vec<String> {
+    // Accept ".pdf" as well as "pdf" in the config
+    match config::current(app_handle).script_extensions {
+        Some(extensions) => extensions
+            .iter()
+            .map(|ext| ext.trim().trim_start_matches('.').to_string())
+            .collect(),
+        None => scripts::DEFAULT_SCRIPT_EXTENSIONS.iter().map(|ext| ext.to_string()).collect(),
+    }
+}
+
+/// Estimated characters, API cost and duration for generating `script_path`, so the UI can
+/// warn before an expensive run. The script is validated locally first.
+#[tauri::command]
+async fn estimate_job(app_handle: AppHandle, script_path: String) -> Result<api::JobEstimate, BackendError> {
+    let extensions = script_extensions(&app_handle);
+    let path = script_path.clone();
+    let validation = tauri::async_runtime::spawn_blocking(move || scripts::validate(Path::new(&path), &extensions))
+        .await
+        .map_err(|e| BackendError::Io(format!("script validation failed: {}", e)))?;
+    if !validation.valid {
+        return Err(BackendError::InvalidArgument(validation.problems.join("; ")));
+    }
+
+    let port = current_backend_port(&app_handle).map_err(BackendError::InvalidPort)?;
+    api::estimate_job(port, &validation.path).await
+}
+
+/// Check a whole script through the backend (parsing, segmenting, voice resolution)
+/// without generating audio, returning per-line errors and warnings. `script_path` may be
+/// absolute or relative to the workspace; anything outside it is rejected.
+#[tauri::command]
+async fn dry_run_script(app_handle: AppHandle, script_path: String) -> Result<api::DryRunReport, BackendError> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled()).map_err(BackendError::Io)?;
+    let extensions = script_extensions(&app_handle);
+    let validation = tauri::async_runtime::spawn_blocking(move || {
+        let file = workspace::resolve_file(&workspace_dir, &script_path)?;
+        Ok::<_, BackendError>(scripts::validate(&file, &extensions))
+    })
+    .await
+    .map_err(|e| BackendError::Io(format!("script validation failed: {}", e)))??;
+    if !validation.valid {
+        return Err(BackendError::InvalidArgument(validation.problems.join("; ")));
+    }
+
+    let port = ready_backend_port(&app_handle)?;
+    let report = api::dry_run_script(port, &validation.path).await?;
+    info!(
+        "Dry run of {}: {} issue(s), valid: {}",
+        validation.path,
+        report.issues.len(),
+        report.valid
+    );
+    Ok(report)
+}
+
+/// Choose whether the bundled backend exits when the app dies (default: on).
+///
+/// The backend watches its stdin, which the app holds open; when the app process goes
+/// away the pipe closes and the backend shuts down gracefully. Turning this off passes
+/// `--ignore-stdin` so the backend outlives a crashed or killed app, e.g. for automation
+/// that reattaches later. A normal quit still stops it. Persisted; applies from the next
+/// backend start. Only the bundled backend is affected: the stdin watch runs in its
+/// `--production` server, while dev backends run under plain `uvicorn.run` and never watch stdin.
+#[tauri::command]
+async fn set_parent_death_shutdown(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    config::update(&app_handle, |config| config.parent_death_shutdown = Some(enabled))?;
+    info!(
+        "Backend shutdown on app exit via stdin {} (applies from the next backend start)",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+/// Copy backend stdout/stderr into the app log file, including in release builds.
+/// Applies immediately (the backend's output is always piped) and is persisted, so a
+/// reproduction run after restarting the app is captured from the first line.
+/// Stdout lines are logged at debug level, so raise the verbosity to see them.
+#[tauri::command]
+async fn set_output_capture(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    config::update(&app_handle, |config| config.capture_output = Some(enabled))?;
+    app_handle.state::<LogPipeline>().set_forwarding(enabled);
+    info!("Backend output capture {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// Set or clear (`None`) the proxy the backend uses for calls to TTS providers.
+/// Persisted and passed as `HTTP(S)_PROXY` at spawn, so it applies from the next backend
+/// start; restart the backend to use it now. Loopback traffic always bypasses it.
+#[tauri::command]
+async fn set_backend_proxy(app_handle: AppHandle, url: Option<String>) -> Result<(), BackendError> {
+    let url = url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    if let Some(url) = &url {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| BackendError::InvalidArgument(format!("invalid proxy URL: {}", e)))?;
+        if !PROXY_SCHEMES.contains(&parsed.scheme()) || parsed.host_str().is_none() {
+            return Err(BackendError::InvalidArgument(format!(
+                "proxy URL must look like http://host:port (supported schemes: {})",
+                PROXY_SCHEMES.join(", ")
+            )));
+        }
+    }
+
+    config::update(&app_handle, |config| config.proxy_url = url.clone()).map_err(BackendError::Io)?;
+    // Never log the URL itself; it may contain credentials
+    info!("Backend proxy {}", if url.is_some() { "set" } else { "cleared" });
+    Ok(())
+}
+
+/// Write a snapshot of the config plus runtime state (mode, running port, latest job)
+/// to `path`, for bug reports or `load_session`. Proxy credentials are left out.
+#[tauri::command]
+async fn save_session(app_handle: AppHandle, path: String) -> Result<session::Session, BackendError> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled()).map_err(BackendError::Io)?;
+    let port = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let process = backend_state.child.lock().unwrap();
+        process.as_ref().map(|running| running.port)
+    };
+
+    let snapshot = session::Session::new(
+        app_handle.package_info().version.to_string(),
+        mode.as_str(),
+        port,
+        workspace_dir.to_string_lossy().into_owned(),
+        config::current(&app_handle),
+        app_handle.state::<LatestJobProgress>().get(),
+    );
+    session::write(Path::new(&path), &snapshot)?;
+    info!("Session saved to {:?}", path);
+    Ok(snapshot)
+}
+
+/// Apply a `save_session` file: replace the config with its snapshot and restart the
+/// backend, on the session's port if it had one. Files from an incompatible version are
+/// rejected before anything changes.
+#[tauri::command]
+async fn load_session(app_handle: AppHandle, path: String) -> Result<StartupTelemetry, String> {
+    let snapshot = tauri::async_runtime::spawn_blocking(move || session::read(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Failed to read session: {}", e))??;
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::Restarting)?;
+
+    info!(
+        "Loading session saved by {} (profile {:?}, port {:?})",
+        snapshot.app_version, snapshot.profile, snapshot.port
+    );
+    shutdown_backend(&app_handle);
+    config::update(&app_handle, |config| *config = snapshot.config.clone())?;
+    launch_backend(&app_handle, snapshot.port.map(u32::from)).await
+}
+
+/// Most recent `backend-job-progress` payload, for UIs that subscribe late
+#[tauri::command]
+async fn get_job_progress(app_handle: AppHandle) -> Result<Option<JobProgress>, String> {
+    Ok(app_handle.state::<LatestJobProgress>().get())
+}
+
+/// Every TCP port the running backend (and its child processes) is listening on,
+/// e.g. to find a metrics port next to the API. Best-effort: an empty list if no backend
+/// is running or enumeration isn't supported on this platform (currently Linux only).
+#[tauri::command]
+async fn get_backend_listening_ports(app_handle: AppHandle) -> Result<Vec<processes::ListeningSocket>, String> {
+    let pid = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let process = backend_state.child.lock().unwrap();
+        process.as_ref().map(|running| running.child.pid())
+    };
+
+    match pid {
+        Some(pid) => tauri::async_runtime::spawn_blocking(move || processes::listening_sockets(pid))
+            .await
+            .map_err(|e| format!("Failed to enumerate listening ports: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Capture a stack dump of every backend thread, for "it's frozen" reports.
+///
+/// Sends SIGUSR1, which the backend hands to `faulthandler`, then returns the stderr lines
+/// that arrive. See `processes::signal_stack_dump` for which processes get the signal.
+/// Windows has no equivalent signal, so this is unsupported there.
+#[tauri::command]
+async fn request_backend_dump(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    #[cfg(unix)]
+    {
+        let (pid, mode) = {
+            let backend_state: State<BackendProcess> = app_handle.state();
+            let process = backend_state.child.lock().unwrap();
+            let running = process.as_ref().ok_or("Backend is not running")?;
+            (running.child.pid(), running.mode)
+        };
+
+        let stderr_buffer: State<BackendStderr> = app_handle.state();
+        stderr_buffer.start_capture();
+
+        let signalled = processes::signal_stack_dump(pid, mode == BackendMode::Development);
+        if signalled == 0 {
+            stderr_buffer.finish_capture();
+            return Err("Failed to signal the backend for a stack dump".to_string());
+        }
+        info!("Requested stack dump from {} backend process(es)", signalled);
+
+        // Wait for the dump to finish arriving: until output stops or the cap is hit
+        let started = Instant::now();
+        let mut last_len = 0;
+        let mut last_change = Instant::now();
+        while started.elapsed() < STACK_DUMP_MAX_WAIT {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let len = stderr_buffer.captured_len();
+            if len != last_len {
+                last_len = len;
+                last_change = Instant::now();
+            } else if len > 0 && last_change.elapsed() >= STACK_DUMP_QUIET {
+                break;
+            }
+        }
+
+        let lines = stderr_buffer.finish_capture();
+        if lines.is_empty() {
+            return Err("Backend did not produce a stack dump; it may be an older version".to_string());
+        }
+        Ok(lines)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = app_handle;
+        Err("Stack dumps are not supported on Windows".to_string())
+    }
+}
+
+/// Restart the backend with a different profile (`None` for the default), leaving the
+/// window alone. `backend-ready` is emitted as for any start. If the new profile fails to
+/// come up, the previous one is restored and restarted. Naming a profile needs a running
+/// backend that reports the `profile` feature; otherwise this fails with `Unsupported`
+/// before anything is saved or stopped.
+#[tauri::command]
+async fn switch_profile(app_handle: AppHandle, profile: Option<String>) -> Result<StartupTelemetry, String> {
+    if let Some(profile) = &profile {
+        api::validate_identifier("profile name", profile)?;
+        require_backend_feature(&app_handle, "profile", "--profile").await?;
+    }
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::Restarting)?;
+
+    let previous = config::current(&app_handle).profile;
+    info!("Switching backend profile from {:?} to {:?}", previous, profile);
+    config::update(&app_handle, |config| config.profile = profile.clone())?;
+
+    shutdown_backend(&app_handle);
+    let error = match launch_backend(&app_handle, None).await {
+        Ok(telemetry) => return Ok(telemetry),
+        Err(e) => e,
+    };
+
+    warn!("Profile {:?} failed to start ({}); restoring {:?}", profile, error, previous);
+    shutdown_backend(&app_handle);
+    let relaunched = match config::update(&app_handle, |config| config.profile = previous.clone()) {
+        Ok(_) => launch_backend(&app_handle, None).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+
+    match relaunched {
+        Ok(()) => Err(format!(
+            "Failed to switch to profile {:?}: {}. Restored profile {:?}.",
+            profile, error, previous
+        )),
+        Err(restore_error) => Err(format!(
+            "Failed to switch to profile {:?}: {}. Restoring profile {:?} also failed: {}",
+            profile, error, previous, restore_error
+        )),
+    }
+}
+
+/// Report which required external tools are on PATH, with versions where available,
+/// so the UI can say "install X" up front instead of failing mid-job.
+/// ffmpeg isn't required by default: the backend fetches its own copy via static-ffmpeg.
+#[tauri::command]
+async fn check_external_tools(app_handle: AppHandle) -> Result<Vec<tools::ToolStatus>, String> {
+    let names = match config::current(&app_handle).required_tools {
+        Some(names) => names,
+        None => {
+            let (mode, _) = resolve_backend_mode(&app_handle)?;
+            if mode.is_bundled() {
+                Vec::new()
+            } else {
+                vec!["uv".to_string()]
+            }
+        }
+    };
+
+    let statuses = tools::check(names).await;
+    for status in statuses.iter().filter(|status| !status.found) {
+        warn!("Required tool not found on PATH: {}", status.name);
+    }
+    Ok(statuses)
+}
+
+/// Result of `clear_backend_cache`
+#[derive(Debug, Clone, Serialize)]
+struct CacheClearResult {
+    bytes_freed: u64,
+    folders_removed: usize,
+}
+
+/// Delete the cached TTS clips of every project to reclaim disk space.
+///
+/// The backend has no cache endpoint, so this deletes the cache folders directly
+/// (see `workspace::clear_caches`). A running backend is stopped first and restarted
+/// afterwards. Emits `backend-cache-clear-progress` while deleting.
+#[tauri::command]
+async fn clear_backend_cache(app_handle: AppHandle) -> Result<CacheClearResult, String> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::ClearingCache)?;
+
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled())?;
+    let was_running = !shutdown_backend(&app_handle).is_empty();
+
+    let app = app_handle.clone();
+    let cleared = tauri::async_runtime::spawn_blocking(move || {
+        workspace::clear_caches(&workspace_dir, |progress| {
+            if let Err(e) = app.emit("backend-cache-clear-progress", progress) {
+                warn!("Failed to emit backend-cache-clear-progress event: {}", e);
+            }
+        })
+    })
+    .await
+    .map_err(|e| format!("Cache clearing task failed: {}", e))
+    .and_then(|result| result);
+
+    // Bring the backend back even if clearing failed part-way
+    if was_running {
+        launch_backend(&app_handle, None).await?;
+    }
+
+    let (bytes_freed, folders_removed) = cleared?;
+    Ok(CacheClearResult {
+        bytes_freed,
+        folders_removed,
+    })
+}
+
+/// Validate a port before it is saved or passed to `start_backend`: rejects privileged
+/// ports and ports the web view refuses to connect to, with a suggested safe range
+#[tauri::command]
+async fn check_backend_port(port: u32) -> Result<u16, BackendError> {
+    port::check(port)
+}
+
+/// Scratch space interrupted generation jobs left in the workspace (`temp_batches` folders
+/// under `output/<project>/`, plus stray write-test files)
+#[tauri::command]
+async fn get_backend_temp_usage(app_handle: AppHandle) -> Result<workspace::TempUsage, String> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled())?;
+    tauri::async_runtime::spawn_blocking(move || workspace::temp_usage(&workspace_dir))
+        .await
+        .map_err(|e| format!("Temp usage check failed: {}", e))?
+}
+
+/// Result of `clean_backend_temp`
+#[derive(Debug, Clone, Serialize)]
+struct TempCleanResult {
+    bytes_freed: u64,
+    entries_removed: usize,
+}
+
+/// Delete what `get_backend_temp_usage` reports. Unlike `clear_backend_cache` this refuses
+/// while the backend runs, since a running job is still writing its batch files.
+#[tauri::command]
+async fn clean_backend_temp(app_handle: AppHandle) -> Result<TempCleanResult, BackendError> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::CleaningTemp)?;
+    if backend_state.child.lock().unwrap().is_some() {
+        return Err(BackendError::OperationInProgress(
+            "stop the backend before cleaning its temp files".to_string(),
+        ));
+    }
+
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled()).map_err(BackendError::Io)?;
+    let (bytes_freed, entries_removed) =
+        tauri::async_runtime::spawn_blocking(move || workspace::clear_temp(&workspace_dir))
+            .await
+            .map_err(|e| BackendError::Io(format!("temp cleanup task failed: {}", e)))?
+            .map_err(BackendError::Io)?;
+    Ok(TempCleanResult {
+        bytes_freed,
+        entries_removed,
+    })
+}
+
+/// Result of `migrate_workspace`
+#[derive(Debug, Clone, Serialize)]
+struct WorkspaceMigration {
+    from: String,
+    to: String,
+    /// Workspace subdirectories that were moved
+    moved: Vec<String>,
+}
+
+/// Move the workspace to `new_path` (e.g. a bigger drive) and restart the backend there
+/// if it was running.
+///
+/// The destination must be empty or not exist yet. Emits `workspace-migration-progress`
+/// while moving. On failure the move is rolled back, including when the new location
+/// cannot be saved, and a backend that was running is restarted against the original
+/// workspace.
+#[tauri::command]
+async fn migrate_workspace(app_handle: AppHandle, new_path: String) -> Result<WorkspaceMigration, String> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::Migrating)?;
+
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let from = get_workspace_dir(&app_handle, mode.is_bundled())?;
+    let to = PathBuf::from(new_path.trim());
+    workspace::validate_destination(&from, &to)?;
+
+    info!("Migrating workspace from {:?} to {:?}", from, to);
+    let was_running = !shutdown_backend(&app_handle).is_empty();
+
+    let app = app_handle.clone();
+    let (src, dst) = (from.clone(), to.clone());
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        workspace::migrate(&src, &dst, |progress| {
+            if let Err(e) = app.emit("workspace-migration-progress", progress) {
+                warn!("Failed to emit workspace-migration-progress event: {}", e);
+            }
+        })
+    })
+    .await
+    .map_err(|e| format!("Workspace migration task failed: {}", e))?;
+
+    let relaunch = |app_handle: AppHandle| async move {
+        if !was_running {
+            return;
+        }
+        if let Err(restart_error) = launch_backend(&app_handle, None).await {
+            warn!("Failed to restart backend after rollback: {}", restart_error);
+        }
+    };
+
+    let moved = match result {
+        Ok(moved) => moved,
+        Err(e) => {
+            error!("Workspace migration failed and was rolled back: {}", e);
+            relaunch(app_handle.clone()).await;
+            return Err(e);
+        }
+    };
+
+    let saved = config::update(&app_handle, |config| {
+        config.workspace_dir = Some(to.to_string_lossy().into_owned());
+    });
+    if let Err(save_error) = saved {
+        // The app would keep using `from`, so the data has to go back there
+        error!("Failed to save the new workspace location, moving it back: {}", save_error);
+        let (src, dst) = (to.clone(), from.clone());
+        let moved_back = tauri::async_runtime::spawn_blocking(move || workspace::migrate(&src, &dst, |_| {}))
+            .await
+            .map_err(|e| format!("Workspace migration task failed: {}", e))
+            .and_then(|result| result);
+        return match moved_back {
+            Ok(_) => {
+                relaunch(app_handle.clone()).await;
+                Err(format!(
+                    "Could not save the new workspace location ({}); the workspace was moved back to {:?}",
+                    save_error, from
+                ))
+            }
+            Err(e) => Err(format!(
+                "Could not save the new workspace location ({}) nor move the workspace back ({}). \
+                 The data is now in {:?} while the app still uses {:?}; move it back by hand, \
+                 or switch the workspace to {:?} once the config can be saved.",
+                save_error, e, to, from, to
+            )),
+        };
+    }
+
+    if was_running {
+        launch_backend(&app_handle, None).await?;
+    }
+    Ok(WorkspaceMigration {
+        from: from.to_string_lossy().into_owned(),
+        to: to.to_string_lossy().into_owned(),
+        moved,
+    })
+}
+
+/// A named workspace preset from the config file
+#[derive(Debug, Clone, Serialize)]
+struct WorkspaceEntry {
+    name: String,
+    path: String,
+    /// The backend is (or will next be) pointed at this directory
+    active: bool,
+    exists: bool,
+}
+
+/// Save a named workspace preset, or remove it when `path` is `None`.
+/// Only records the location; use `switch_workspace` to start using it.
+#[tauri::command]
+async fn save_workspace(app_handle: AppHandle, name: String, path: Option<String>) -> Result<(), BackendError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(BackendError::InvalidArgument("workspace name is empty".to_string()));
+    }
+    let path = match path.as_deref().map(str::trim) {
+        Some(path) if !PathBuf::from(path).is_absolute() => {
+            return Err(BackendError::InvalidArgument(format!(
+                "workspace path {:?} must be absolute",
+                path
+            )));
+        }
+        other => other.map(str::to_string),
+    };
+
+    config::update(&app_handle, |config| {
+        let workspaces = config.workspaces.get_or_insert_with(Default::default);
+        match &path {
+            Some(path) => {
+                workspaces.insert(name.clone(), path.clone());
+            }
+            None => {
+                workspaces.remove(&name);
+            }
+        }
+    })
+    .map_err(BackendError::Io)?;
+    info!("Workspace preset {:?} set to {:?}", name, path);
+    Ok(())
+}
+
+/// Named workspace presets, marking the one the backend currently uses
+#[tauri::command]
+async fn list_workspaces(app_handle: AppHandle) -> Result<Vec<WorkspaceEntry>, String> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let current = get_workspace_dir(&app_handle, mode.is_bundled())?;
+
+    let workspaces = config::current(&app_handle).workspaces.unwrap_or_default();
+    Ok(workspaces
+        .into_iter()
+        .map(|(name, path)| {
+            let dir = PathBuf::from(&path);
+            WorkspaceEntry {
+                active: dir == current,
+                exists: dir.is_dir(),
+                name,
+                path,
+            }
+        })
+        .collect())
+}
+
+/// Point the backend at a named workspace preset (`None` for the default location).
+/// The old backend is stopped before the workspace changes, then restarted against the
+/// new directory. Nothing is moved; see `migrate_workspace` for that. If the backend
+/// fails to start there, the previous workspace is restored.
+#[tauri::command]
+async fn switch_workspace(app_handle: AppHandle, name: Option<String>) -> Result<StartupTelemetry, String> {
+    let target = match &name {
+        Some(name) => {
+            let workspaces = config::current(&app_handle).workspaces.unwrap_or_default();
+            let path = workspaces
+                .get(name.trim())
+                .ok_or_else(|| BackendError::NotFound(format!("no workspace named {:?}", name)))?;
+            let dir = PathBuf::from(path);
+            // A missing directory is fine: spawn_backend creates the expected structure
+            if !dir.is_absolute() || (dir.exists() && !dir.is_dir()) {
+                return Err(BackendError::InvalidConfig(format!(
+                    "workspace {:?} path {:?} must be an absolute directory",
+                    name, path
+                ))
+                .into());
+            }
+            Some(path.clone())
+        }
+        None => None,
+    };
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _operation = backend_state.begin_operation(BackendOperation::SwitchingWorkspace)?;
+
+    let previous = config::current(&app_handle).workspace_dir;
+    info!("Switching workspace from {:?} to {:?} ({:?})", previous, target, name);
+
+    shutdown_backend(&app_handle);
+    config::update(&app_handle, |config| config.workspace_dir = target.clone())?;
+    let error = match launch_backend(&app_handle, None).await {
+        Ok(telemetry) => return Ok(telemetry),
+        Err(e) => e,
+    };
+
+    warn!("Backend failed to start in workspace {:?} ({}); restoring {:?}", target, error, previous);
+    shutdown_backend(&app_handle);
+    let relaunched = match config::update(&app_handle, |config| config.workspace_dir = previous.clone()) {
+        Ok(_) => launch_backend(&app_handle, None).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+
+    match relaunched {
+        Ok(()) => Err(format!(
+            "Failed to switch to workspace {:?}: {}. Restored the previous workspace.",
+            name, error
+        )),
+        Err(restore_error) => Err(format!(
+            "Failed to switch to workspace {:?}: {}. Restoring the previous workspace also failed: {}",
+            name, error, restore_error
+        )),
+    }
+}
+
+/// Open a file from the workspace (e.g. a generated mp3) with the OS default application.
+/// `path` may be absolute or relative to the workspace; anything outside it is rejected.
+#[tauri::command]
+async fn open_file(app_handle: AppHandle, path: String) -> Result<String, BackendError> {
+    let (mode, _) = resolve_backend_mode(&app_handle)?;
+    let workspace_dir = get_workspace_dir(&app_handle, mode.is_bundled()).map_err(BackendError::Io)?;
+    let file = workspace::resolve_file(&workspace_dir, &path)?;
+
+    let file = file.to_string_lossy().into_owned();
+    app_handle
+        .opener()
+        .open_path(file.as_str(), None::<&str>)
+        .map_err(|e| BackendError::Io(format!("failed to open {}: {}", file, e)))?;
+
+    info!("Opened file: {}", file);
+    Ok(file)
+}
+
+/// Open the raw AppLocalData directory (config, logs) in the OS file manager.
+/// Independent of the workspace, which may live elsewhere in dev mode.
+#[tauri::command]
+async fn open_app_data_dir(app_handle: AppHandle) -> Result<String, String> {
+    use tauri::path::BaseDirectory;
+
+    let app_data_dir = app_handle
+        .path()
+        .resolve("", BaseDirectory::AppLocalData)
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory {:?}: {}", app_data_dir, e))?;
+
+    let path = app_data_dir
+        .to_str()
+        .ok_or_else(|| "Failed to convert app data path to string".to_string())?
+        .to_string();
+
+    app_handle
+        .opener()
+        .open_path(path.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open app data directory {}: {}", path, e))?;
+
+    info!("Opened app data directory: {}", path);
+    Ok(path)
+}
+
+/// Most recent backend stderr output, with the last Python traceback extracted
+/// so the UI can show the whole block rather than a single line
+#[tauri::command]
+async fn get_last_backend_error(app_handle: AppHandle) -> Result<Option<LastBackendError>, String> {
+    let stderr_buffer: State<BackendStderr> = app_handle.state();
+    Ok(stderr_buffer.last_error())
+}
+
+/// Trigger model/voice loading so the first real generation isn't a cold start.
+/// Emits `backend-warmed` when done, so callers may ignore the returned future.
+#[tauri::command]
+async fn prewarm_backend(app_handle: AppHandle) -> Result<WarmupResult, String> {
+    let port = current_backend_port(&app_handle)?;
+    let result = api::prewarm(port).await?;
+
+    if let Err(e) = app_handle.emit("backend-warmed", &result) {
+        warn!("Failed to emit backend-warmed event: {}", e);
+    }
+    Ok(result)
+}
+
+/// Check that a provider's API key is configured before starting a long job
+#[tauri::command]
+async fn test_provider_credentials(
+    app_handle: AppHandle,
+    provider: String,
+) -> Result<CredentialTestResult, BackendError> {
+    let port = current_backend_port(&app_handle).map_err(BackendError::InvalidPort)?;
+    api::test_provider_credentials(port, &provider).await
+}
+
+/// Request counts, error rates and latencies per backend path, as seen by `backend_request`
+/// since startup or the last `reset`. Paths are grouped with ids collapsed to `{id}`.
+#[tauri::command]
+async fn get_backend_metrics(app_handle: AppHandle, reset: Option<bool>) -> Result<MetricsSnapshot, String> {
+    Ok(app_handle.state::<RequestMetrics>().snapshot(reset.unwrap_or(false)))
+}
+
+/// Remaining characters/credits for `provider`, so the UI can warn before a job would run
+/// out. `None` when the provider doesn't report quota. Answers are reused for a minute.
+#[tauri::command]
+async fn get_provider_quota(app_handle: AppHandle, provider: String) -> Result<Option<api::ProviderQuota>, BackendError> {
+    let port = ready_backend_port(&app_handle)?;
+    app_handle.state::<api::QuotaCache>().get(port, &provider).await
+}
+
+/// Result of `refresh_backend_providers`, also the payload of `providers-changed`
+#[derive(Debug, Clone, Serialize)]
+struct ProviderRefresh {
+    providers: Vec<String>,
+    /// The backend could not reload in place, so it was restarted
+    restarted: bool,
+}
+
+/// Make the backend pick up new credentials or provider configs and return the fresh
+/// provider list. Backends without hot reload are restarted instead, which interrupts
+/// running work; `restarted` tells the UI which happened. Cached capabilities and quotas
+/// are dropped either way, and `providers-changed` is emitted.
+#[tauri::command]
+async fn refresh_backend_providers(app_handle: AppHandle) -> Result<ProviderRefresh, BackendError> {
+    let port = ready_backend_port(&app_handle)?;
+    let (port, restarted) = match api::reload_providers(port).await {
+        Ok(()) => {
+            let backend_state: State<BackendProcess> = app_handle.state();
+            if let Some(running) = backend_state.child.lock().unwrap().as_mut() {
+                running.capabilities = None;
+            }
+            (port, false)
+        }
+        Err(BackendError::Unsupported(e)) => {
+            info!("{}; restarting the backend to reload providers", e);
+            let telemetry = restart_backend(app_handle.clone(), None).await.map_err(|e| {
+                BackendError::Unavailable(format!("restart to reload providers failed: {}", e))
+            })?;
+            (telemetry.port, true)
+        }
+        Err(e) => return Err(e),
+    };
+
+    app_handle.state::<api::QuotaCache>().clear();
+
+    let refresh = ProviderRefresh {
+        providers: api::providers(port).await?,
+        restarted,
+    };
+    info!("Backend providers refreshed: {:?}", refresh);
+    if let Err(e) = app_handle.emit("providers-changed", &refresh) {
+        warn!("Failed to emit providers-changed event: {}", e);
+    }
+    Ok(refresh)
+}
+
+/// Per-provider reachability and latency as seen from the backend, so a failure can be
+/// pinned on the local setup, a proxy, or the provider itself
+#[tauri::command]
+async fn check_provider_connectivity(app_handle: AppHandle) -> Result<Vec<api::ProviderConnectivity>, BackendError> {
+    let port = current_backend_port(&app_handle).map_err(BackendError::InvalidPort)?;
+    api::check_provider_connectivity(port).await
+}
+
+/// Forward a request to the backend. Connection failures during a start/restart are
+/// retried briefly, so early requests wait for the backend instead of failing.
+/// `timeout_ms` defaults to the session default (see `set_default_request_timeout`); pass a
+/// short value for metadata calls.
+#[tauri::command]
+async fn backend_request(
+    app_handle: AppHandle,
+    method: String,
+    path: String,
+    body: Option<serde_json::Value>,
+    timeout_ms: Option<u64>,
+) -> Result<ProxyResponse, BackendError> {
+    let timeout = match timeout_ms {
+        Some(0) => {
+            return Err(BackendError::InvalidArgument(
+                "timeout_ms must be greater than 0".to_string(),
+            ))
+        }
+        Some(ms) => Duration::from_millis(ms),
+        None => app_handle.state::<api::ProxyTimeout>().get(),
+    };
+
+    let started = Instant::now();
+    loop {
+        let port = current_backend_port(&app_handle).map_err(BackendError::InvalidPort)?;
+        let attempt_started = Instant::now();
+        match api::proxy(port, &method, &path, body.clone(), timeout).await {
+            Err(BackendError::Unavailable(e))
+                if backend_starting(&app_handle) && started.elapsed() < STARTUP_RETRY_WINDOW =>
+            {
+                debug!("Backend still starting, retrying {} {}: {}", method, path, e);
+                tokio::time::sleep(STARTUP_RETRY_INTERVAL).await;
+            }
+            result => {
+                let outcome = match &result {
+                    Ok(response) => metrics::Outcome::Status(response.status),
+                    // Rejected before anything was sent
+                    Err(BackendError::InvalidArgument(_)) => return result,
+                    Err(_) => metrics::Outcome::Failed,
+                };
+                app_handle
+                    .state::<RequestMetrics>()
+                    .record(&method, &path, outcome, attempt_started.elapsed());
+                return result;
+            }
+        }
+    }
+}
+
+/// Set the timeout `backend_request` uses when a call doesn't pass one, for the rest of the
+/// session (`None` restores the built-in 10 minutes). Clamped to 1 s - 1 h; returns the
+/// value in effect, in milliseconds.
+#[tauri::command]
+async fn set_default_request_timeout(app_handle: AppHandle, timeout_ms: Option<u64>) -> Result<u64, BackendError> {
+    let timeout = app_handle.state::<api::ProxyTimeout>().set(timeout_ms)?;
+    Ok(timeout.as_millis() as u64)
+}
+
+/// Give up on a start/restart that is taking too long: stops waiting for readiness,
+/// kills the spawned process and emits `backend-startup-aborted`.
+/// The pending `start_backend`/`restart_backend` call then fails with an "aborted" error.
+#[tauri::command]
+async fn abort_startup(app_handle: AppHandle) -> Result<(), String> {
+    if !backend_starting(&app_handle) {
+        return Err("Backend is not starting".to_string());
+    }
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let sender = backend_state.startup_abort.lock().unwrap().take();
+    match sender {
+        Some(sender) => {
+            let _ = sender.send(());
+            Ok(())
+        }
+        // A restart that is still stopping the old backend; the window is very short
+        None => Err("Startup cannot be aborted yet, please try again".to_string()),
+    }
+}
+
+/// Whether a start or restart is currently in flight
+fn backend_starting(app_handle: &AppHandle) -> bool {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let operation = *backend_state.operation.lock().unwrap();
+    matches!(operation, Some(BackendOperation::Starting | BackendOperation::Restarting))
+}
+
+/// Base URL for direct HTTP calls from the webview.
+///
+/// Fails with `Unavailable` until the backend has passed its health check, so callers
+/// never see connection-refused errors during startup. The frontend should await the
+/// `backend-ready` event (or retry this) before issuing requests.
+#[tauri::command]
+async fn get_api_base_url(app_handle: AppHandle) -> Result<String, BackendError> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let process = backend_state.child.lock().unwrap();
+    match process.as_ref() {
+        Some(running) if running.ready => Ok(health::backend_url(running.port, "")),
+        _ => Err(BackendError::Unavailable(
+            "backend is not ready yet; wait for backend-ready".to_string(),
+        )),
+    }
+}
+
+/// Everything the webview needs to build its HTTP client, returned by `get_api_client_config`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiClientConfig {
+    base_url: String,
+    /// Default request timeout (`set_default_request_timeout`)
+    timeout_ms: u64,
+    health_path: String,
+}
+
+/// Base URL, default timeout and health path in one call. There is no auth token: the
+/// backend only listens on loopback and does not authenticate requests.
+///
+/// Like `get_api_base_url`, fails with `Unavailable` until the backend has passed its
+/// health check; await `backend-ready` (or retry) first.
+#[tauri::command]
+async fn get_api_client_config(app_handle: AppHandle) -> Result<ApiClientConfig, BackendError> {
+    let base_url = get_api_base_url(app_handle.clone()).await?;
+    let health_path = health::resolve_health_path(&config::current(&app_handle))?;
+    let timeout = app_handle.state::<api::ProxyTimeout>().get();
+    Ok(ApiClientConfig {
+        base_url,
+        timeout_ms: timeout.as_millis() as u64,
+        health_path,
+    })
+}
+
+/// Store a provider API key in the OS keychain (empty `value` removes it).
+/// Takes effect on the next backend start, when stored keys are passed in the environment.
+#[tauri::command]
+async fn set_secret(provider: String, value: String) -> Result<(), BackendError> {
+    tauri::async_runtime::spawn_blocking(move || secrets::set(&provider, &value))
+        .await
+        .map_err(|e| BackendError::Io(e.to_string()))?
+}
+
+/// Whether a provider API key is stored in the keychain; the value is never returned
+#[tauri::command]
+async fn has_secret(provider: String) -> Result<bool, BackendError> {
+    tauri::async_runtime::spawn_blocking(move || secrets::has(&provider))
+        .await
+        .map_err(|e| BackendError::Io(e.to_string()))?
+}
+
+/// Time one standardized generation of `sample` against the live backend.
+/// Defaults to the dummy provider; pass `provider`/`config` to compare real providers.
+#[tauri::command]
+async fn run_backend_benchmark(
+    app_handle: AppHandle,
+    sample: String,
+    provider: Option<String>,
+    config: Option<serde_json::Value>,
+) -> Result<BenchmarkResult, BackendError> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let _running = backend_state.benchmark.try_lock().map_err(|_| {
+        BackendError::OperationInProgress("a benchmark is already running".to_string())
+    })?;
+
+    let port = ready_backend_port(&app_handle)?;
+    let provider = provider.unwrap_or_else(|| api::BENCHMARK_DEFAULT_PROVIDER.to_string());
+    let config = config.unwrap_or_else(|| serde_json::json!({}));
+    api::benchmark(port, &provider, config, &sample, api::DEFAULT_PROXY_TIMEOUT).await
+}
+
+/// Port of the running backend once it has passed its health check
+fn ready_backend_port(app_handle: &AppHandle) -> Result<u16, BackendError> {
+    with_ready_backend(app_handle, |running| running.port)
+}
+
+/// Read from the running backend once it has passed its health check; `Unavailable` otherwise.
+/// The state lock is held only while `read` runs.
+fn with_ready_backend<T>(app_handle: &AppHandle, read: impl FnOnce(&RunningBackend) -> T) -> Result<T, BackendError> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let process = backend_state.child.lock().unwrap();
+    process
+        .as_ref()
+        .filter(|running| running.ready)
+        .map(read)
+        .ok_or_else(|| BackendError::Unavailable("backend is not running or not ready yet".to_string()))
+}
+
+/// Reject a file destination that is a directory or whose folder doesn't exist
+fn check_output_file(destination: &Path) -> Result<(), BackendError> {
+    if destination.is_dir() {
+        return Err(BackendError::InvalidArgument(format!(
+            "{:?} is a directory, not a file",
+            destination
+        )));
+    }
+    if !destination.parent().is_some_and(Path::is_dir) {
+        return Err(BackendError::NotFound(format!(
+            "destination folder for {:?} does not exist",
+            destination
+        )));
+    }
+    Ok(())
+}
+
+/// Save a PNG of the main window's content to `destination` for a bug report, returning the
+/// path written. Fails with `Unavailable` while the window is hidden or minimized, since
+/// there is nothing on screen to capture. See `screenshot::capture` for platform notes.
+#[tauri::command]
+async fn capture_window_screenshot(app_handle: AppHandle, destination: String) -> Result<String, BackendError> {
+    let destination = PathBuf::from(destination);
+    check_output_file(&destination)?;
+
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| BackendError::Unavailable("the main window is not open".to_string()))?;
+    let window_error = |e: tauri::Error| BackendError::Io(format!("failed to read window geometry: {}", e));
+    if !window.is_visible().map_err(window_error)? || window.is_minimized().map_err(window_error)? {
+        return Err(BackendError::Unavailable(
+            "the window is hidden or minimized; show it and try again".to_string(),
+        ));
+    }
+    let position = window.inner_position().map_err(window_error)?;
+    let size = window.inner_size().map_err(window_error)?;
+    let region = screenshot::Region {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        scale_factor: window.scale_factor().map_err(window_error)?,
+    };
+
+    let path = destination.clone();
+    tauri::async_runtime::spawn_blocking(move || screenshot::capture(region, &path))
+        .await
+        .map_err(|e| BackendError::Io(format!("screenshot task failed: {}", e)))??;
+    Ok(destination.display().to_string())
+}
+
+/// Generate `text` and stream the audio straight into `destination` (typically picked with
+/// the save dialog), emitting `generation-download-progress` as bytes arrive. Large files
+/// never pass through the webview. Only one download runs at a time; a cancelled or failed
+/// download leaves no partial file behind.
+#[tauri::command]
+async fn stream_generation_to_file(
+    app_handle: AppHandle,
+    text: String,
+    provider: String,
+    config: Option<serde_json::Value>,
+    destination: String,
+) -> Result<api::DownloadProgress, BackendError> {
+    let destination = PathBuf::from(destination);
+    check_output_file(&destination)?;
+
+    let port = ready_backend_port(&app_handle)?;
+    let cancel = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let mut download = backend_state.generation_download.lock().unwrap();
+        if download.is_some() {
+            return Err(BackendError::OperationInProgress(
+                "a generation download is already running".to_string(),
+            ));
+        }
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        *download = Some(sender);
+        receiver
+    };
+
+    let config = config.unwrap_or_else(|| serde_json::json!({}));
+    let result = api::generate_to_file(
+        port,
+        &provider,
+        config,
+        &text,
+        &destination,
+        cancel,
+        |progress| {
+            if let Err(e) = app_handle.emit("generation-download-progress", progress) {
+                warn!("Failed to emit generation-download-progress event: {}", e);
+            }
+        },
+    )
+    .await;
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    backend_state.generation_download.lock().unwrap().take();
+    result
+}
+
+/// Stop the running `stream_generation_to_file`; it then fails with `cancelled`
+#[tauri::command]
+fn cancel_generation_download(app_handle: AppHandle) -> Result<(), BackendError> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let sender = backend_state.generation_download.lock().unwrap().take();
+    match sender {
+        Some(sender) => {
+            let _ = sender.send(());
+            Ok(())
+        }
+        None => Err(BackendError::NotFound("no generation download is running".to_string())),
+    }
+}
+
+/// Optional features of the running backend, so the UI can hide what it doesn't support.
+/// Fetched once per backend start (right after it is ready); a backend without the
+/// endpoint reports none.
+#[tauri::command]
+async fn get_backend_capabilities(app_handle: AppHandle) -> Result<BackendCapabilities, BackendError> {
+    let (pid, port) = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let process = backend_state.child.lock().unwrap();
+        let running = process
+            .as_ref()
+            .ok_or_else(|| BackendError::Unavailable("backend is not running".to_string()))?;
+        if let Some(capabilities) = &running.capabilities {
+            return Ok(capabilities.clone());
+        }
+        (running.child.pid(), running.port)
+    };
+
+    let capabilities = api::capabilities(port).await?;
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let mut process = backend_state.child.lock().unwrap();
+    if let Some(running) = process.as_mut().filter(|running| running.child.pid() == pid) {
+        running.capabilities = Some(capabilities.clone());
+    }
+    Ok(capabilities)
+}
+
+/// Whether the running backend supports `feature` (e.g. `cancellation`, `streaming`,
+/// `reload`), from the cached capabilities. Unknown features and backends without the
+/// capabilities endpoint answer `false`.
+#[tauri::command]
+async fn backend_supports(app_handle: AppHandle, feature: String) -> Result<bool, String> {
+    let capabilities = get_backend_capabilities(app_handle).await?;
+    Ok(capabilities.supports(feature.trim()))
+}
+
+/// Fail with `Unsupported` unless the running backend reports `feature`, for launch flags it
+/// would otherwise silently drop. Without a running backend there is nothing to ask, so this
+/// fails with `Unavailable`.
+async fn require_backend_feature(app_handle: &AppHandle, feature: &str, flag: &str) -> Result<(), BackendError> {
+    let capabilities = get_backend_capabilities(app_handle.clone()).await?;
+    if capabilities.supports(feature) {
+        Ok(())
+    } else {
+        Err(BackendError::Unsupported(format!(
+            "the backend does not accept {} (no `{}` capability)",
+            flag, feature
+        )))
+    }
+}
+
+/// Read the backend's audio output settings and cache them on `pid`'s state
+async fn fetch_audio_config(
+    app_handle: &AppHandle,
+    pid: u32,
+    port: u16,
+) -> Result<AudioOutputConfig, BackendError> {
+    let audio_config = api::audio_config(port).await?;
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let mut process = backend_state.child.lock().unwrap();
+    if let Some(running) = process.as_mut().filter(|running| running.child.pid() == pid) {
+        running.audio_config = Some(audio_config.clone());
+    }
+    Ok(audio_config)
+}
+
+/// Output format, sample rate and bitrate of the running backend, so the UI only offers
+/// what it can produce. Cached from startup; pass `refresh` after changing backend settings.
+#[tauri::command]
+async fn get_backend_audio_config(
+    app_handle: AppHandle,
+    refresh: Option<bool>,
+) -> Result<AudioOutputConfig, BackendError> {
+    let (pid, port) = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let process = backend_state.child.lock().unwrap();
+        let running = process
+            .as_ref()
+            .ok_or_else(|| BackendError::Unavailable("backend is not running".to_string()))?;
+        if let (Some(audio_config), false) = (&running.audio_config, refresh.unwrap_or(false)) {
+            return Ok(audio_config.clone());
+        }
+        (running.child.pid(), running.port)
+    };
+
+    fetch_audio_config(&app_handle, pid, port).await
+}
+
+/// Program, arguments, working directory and extra env vars the running backend was
+/// spawned with, secrets redacted, so support can see exactly how it was invoked
+#[tauri::command]
+async fn get_backend_launch_command(app_handle: AppHandle) -> Result<LaunchCommand, BackendError> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let process = backend_state.child.lock().unwrap();
+    process
+        .as_ref()
+        .map(|running| running.launch_command.clone())
+        .ok_or_else(|| BackendError::Unavailable("backend is not running".to_string()))
+}
+
+/// A setting where the backend's resolved value differs from what it was launched with
+#[derive(Debug, Clone, Serialize)]
+struct ConfigMismatch {
+    key: &'static str,
+    passed: serde_json::Value,
+    applied: serde_json::Value,
+}
+
+/// Payload of `get_backend_effective_config`
+#[derive(Debug, Clone, Serialize)]
+struct BackendEffectiveConfig {
+    /// Settings as the backend resolved them, secrets redacted
+    config: serde_json::Value,
+    /// Handed-off settings the backend reports differently
+    mismatches: Vec<ConfigMismatch>,
+}
+
+/// What the backend itself resolved (port, workspace, profile, ...), as opposed to
+/// `get_effective_config`, which reports the GUI side. Settings the GUI passed at spawn
+/// are compared against it so a broken handoff shows up as a mismatch.
+#[tauri::command]
+async fn get_backend_effective_config(app_handle: AppHandle) -> Result<BackendEffectiveConfig, BackendError> {
+    let (port, passed) = with_ready_backend(&app_handle, |running| {
+        let workspace_dir = running
+            .launch_command
+            .env
+            .iter()
+            .find(|(name, _)| name == workspace::BACKEND_WORKSPACE_ENV_VAR)
+            .map(|(_, value)| value.clone());
+        let passed = [
+            ("port", Some(serde_json::json!(running.port))),
+            ("workspace_dir", workspace_dir.map(serde_json::Value::from)),
+            ("profile", running.profile.clone().map(serde_json::Value::from)),
+            ("workers", running.workers.map(serde_json::Value::from)),
+        ];
+        (running.port, passed)
+    })?;
+
+    let config = api::backend_config(port).await?;
+    let mismatches = passed
+        .into_iter()
+        .filter_map(|(key, passed)| {
+            let passed = passed?;
+            let applied = config.get(key)?.clone();
+            let same = match (passed.as_str(), applied.as_str()) {
+                (Some(passed), Some(applied)) => Path::new(passed) == Path::new(applied),
+                _ => passed == applied,
+            };
+            (!same).then_some(ConfigMismatch { key, passed, applied })
+        })
+        .collect::<Vec<_>>();
+    if !mismatches.is_empty() {
+        warn!("Backend resolved settings differently from what it was launched with: {:?}", mismatches);
+    }
+
+    Ok(BackendEffectiveConfig { config, mismatches })
+}
+
+/// Resolve once the backend is in `target` state (`stopped`, `starting`, `ready` or
+/// `stopping`), immediately if it already is, or fail with a timeout after `timeout_ms`.
+/// Woken by state changes rather than polling.
+#[tauri::command]
+async fn await_backend_state(
+    app_handle: AppHandle,
+    target: String,
+    timeout_ms: u64,
+) -> Result<BackendState, BackendError> {
+    let target = BackendState::parse(&target).ok_or_else(|| {
+        BackendError::InvalidArgument(format!(
+            "unknown backend state {:?} (expected stopped, starting, ready or stopping)",
+            target
+        ))
+    })?;
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(timeout_ms);
+    loop {
+        // Register before checking so a change between the check and the wait isn't missed
+        let notified = backend_state.state_changed.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+
+        let current = backend_state.lifecycle_state();
+        if current == target {
+            return Ok(current);
+        }
+        if tokio::time::timeout_at(deadline, notified).await.is_err() {
+            return Err(BackendError::Timeout(format!(
+                "backend did not become {} within {} ms (currently {})",
+                target.as_str(),
+                timeout_ms,
+                backend_state.lifecycle_state().as_str()
+            )));
+        }
+    }
+}
+
+/// Recent backend crashes (including earlier launches) with exit code/signal and the
+/// stderr that preceded them, oldest first. `limit` keeps only the newest ones.
+#[tauri::command]
+async fn get_crash_history(app_handle: AppHandle, limit: Option<usize>) -> Result<Vec<CrashRecord>, String> {
+    Ok(app_handle.state::<CrashHistory>().snapshot(limit))
+}
+
+/// Durations of recent backend startups (including earlier launches), oldest first,
+/// so a gradual slow-down is visible
+#[tauri::command]
+async fn get_startup_history(app_handle: AppHandle) -> Result<Vec<StartupRecord>, String> {
+    Ok(app_handle.state::<StartupHistory>().snapshot())
+}
+
+/// Stop the backend's HTTP server from taking new work and wait until in-flight requests
+/// have drained, without killing the process (e.g. to flush caches before the final stop).
+/// The watchdog ignores the backend from then on; stop or restart it to recover.
+#[tauri::command]
+async fn quiesce_backend(app_handle: AppHandle) -> Result<api::QuiesceResult, BackendError> {
+    let (pid, port) = with_ready_backend(&app_handle, |running| (running.child.pid(), running.port))?;
+
+    info!("Quiescing backend (PID: {})", pid);
+    let result = api::quiesce(port).await?;
+
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let mut process = backend_state.child.lock().unwrap();
+    if let Some(running) = process.as_mut().filter(|running| running.child.pid() == pid) {
+        running.quiesced = true;
+    }
+    if !result.drained {
+        warn!("Backend quiesced with requests still pending: {:?}", result.pending_requests);
+    }
+    Ok(result)
+}
+
+/// Cancel all in-flight generation jobs right away instead of letting them drain, so no
+/// further provider API calls are made. Waits a few seconds for the backend to confirm and
+/// reports how many tasks it confirmed cancelled. The app also does this on exit (see
+/// `cancel_jobs_before_exit`). Fails with `Unsupported` on backends that cannot list their jobs.
+#[tauri::command]
+async fn cancel_all_jobs(app_handle: AppHandle) -> Result<api::CancelAllResult, BackendError> {
+    let port = ready_backend_port(&app_handle)?;
+    api::cancel_all_jobs(port, api::CANCEL_CONFIRM_TIMEOUT).await
+}
+
+/// Ask the backend to cancel its jobs before it is killed on exit, so a job mid-way through
+/// stops calling provider APIs at once rather than when the kill lands. Only the cancel
+/// requests are awaited, never the confirmation, and the whole step is capped at
+/// `EXIT_CANCEL_TIMEOUT` so an unresponsive backend cannot hold up quitting.
+async fn cancel_jobs_before_exit(app_handle: &AppHandle) {
+    let Ok(port) = ready_backend_port(app_handle) else {
+        return;
+    };
+    match tokio::time::timeout(EXIT_CANCEL_TIMEOUT, api::cancel_all_jobs(port, Duration::ZERO)).await {
+        Ok(Ok(result)) if result.requested > 0 => {
+            info!("Cancelled {} backend job(s) before exit", result.requested)
+        }
+        Ok(Ok(_)) => {}
+        Ok(Err(BackendError::Unsupported(_))) => debug!("Backend cannot cancel jobs; stopping it directly"),
+        Ok(Err(e)) => warn!("Failed to cancel backend jobs before exit: {}", e),
+        Err(_) => warn!("Cancelling backend jobs before exit timed out"),
+    }
+}
+
+/// Whether this is the primary instance, plus how many later launches the single-instance
+/// plugin forwarded here and the latest one's arguments, so automation can tell whether
+/// its launch started a backend or was folded into the running app.
+///
+/// `primary` is always true: a second instance hands over its arguments and exits during
+/// plugin setup, before any window or command handler exists, so only the primary can answer.
+#[tauri::command]
+async fn is_primary_instance(app_handle: AppHandle) -> Result<InstanceInfo, String> {
+    let instance = app_handle.state::<InstanceState>();
+    let last_secondary_launch = instance.last_secondary_launch.lock().unwrap().clone();
+    Ok(InstanceInfo {
+        primary: true,
+        secondary_launches: instance.secondary_launches.load(Ordering::Relaxed),
+        last_secondary_launch,
+    })
+}
+
+/// One-line summary for the status bar, e.g. "Production • profile: default • port 58735".
+/// Describes the running process if there is one, otherwise what the next launch would use.
+#[tauri::command]
+async fn get_backend_mode_label(app_handle: AppHandle) -> Result<String, String> {
+    let running = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let process = backend_state.child.lock().unwrap();
+        process
+            .as_ref()
+            .map(|running| (running.mode, running.profile.clone(), running.workers, running.port))
+    };
+
+    let (mode, profile, workers, port, stopped) = match running {
+        Some((mode, profile, workers, port)) => (mode, profile, workers, port, false),
+        None => {
+            let config = config::current(&app_handle);
+            let (mode, _) = resolve_backend_mode(&app_handle)?;
+            let port = current_backend_port(&app_handle)?;
+            (mode, config.profile, config.workers, port, true)
+        }
+    };
+
+    let mut parts = vec![
+        mode.label().to_string(),
+        format!("profile: {}", profile.as_deref().unwrap_or("default")),
+    ];
+    if let Some(workers) = workers {
+        parts.push(format!("workers: {}", workers));
+    }
+    parts.push(format!("port {}", port));
+    if stopped {
+        parts.push("stopped".to_string());
+    }
+    Ok(parts.join(" \u{2022} "))
+}
+
+/// Which port the last successful start asked for and which one the backend actually got,
+/// so the UI can explain a URL that differs from the expected one. `None` until a backend
+/// has become ready.
+#[tauri::command]
+async fn get_port_resolution(app_handle: AppHandle) -> Result<Option<PortResolution>, String> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    let resolution = backend_state.port_resolution.lock().unwrap().clone();
+    Ok(resolution)
+}
+
+/// Port of the running backend, or the port the next launch would use
+fn current_backend_port(app_handle: &AppHandle) -> Result<u16, String> {
+    let backend_state: State<BackendProcess> = app_handle.state();
+    if let Some(ref running) = *backend_state.child.lock().unwrap() {
+        return Ok(running.port);
+    }
+
+    let (mode, _) = resolve_backend_mode(app_handle)?;
+    let port = port::resolve_backend_port(None, &config::current(app_handle), default_port(mode.is_bundled()))?;
+    Ok(port)
+}
+
+#[tauri::command]
+async fn get_backend_port(app_handle: AppHandle) -> Result<u16, String> {
+    current_backend_port(&app_handle)
+}
+
+/// Single health probe; `false` means the backend is down or unhealthy
+#[tauri::command]
+async fn ping_backend(app_handle: AppHandle) -> Result<bool, String> {
+    let port = current_backend_port(&app_handle)?;
+    let health_path = health::resolve_health_path(&config::current(&app_handle))?;
+
+    match health::probe(port, &health_path).await {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            debug!("Ping failed: {}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Round-trip time of a health request in milliseconds
+#[tauri::command]
+async fn measure_backend_latency(app_handle: AppHandle) -> Result<u64, String> {
+    let port = current_backend_port(&app_handle)?;
+    let health_path = health::resolve_health_path(&config::current(&app_handle))?;
+
+    let elapsed = health::probe(port, &health_path).await?;
+    Ok(elapsed.as_millis() as u64)
+}
+
+/// Everything the status panel needs, gathered in one call so the parts agree
+#[derive(Debug, Clone, Serialize)]
+struct HealthSummary {
+    /// A backend is tracked and its process is alive
+    running: bool,
+    pid: Option<u32>,
+    mode: Option<BackendMode>,
+    port: u16,
+    /// Lifecycle operation in flight (e.g. "start" or "workspace migration"), if any
+    operation: Option<&'static str>,
+    /// Set via `set_maintenance_mode`; failures are not reported for recovery
+    maintenance: bool,
+    uptime_ms: Option<u64>,
+    /// The health endpoint answered with a 2xx
+    healthy: bool,
+    health_latency_ms: Option<u64>,
+    health_error: Option<String>,
+    last_error: Option<LastBackendError>,
+}
+
+/// Snapshot of process liveness, HTTP health, port, uptime and last error.
+/// The liveness check and health probe run concurrently.
+#[tauri::command]
+async fn get_health_summary(app_handle: AppHandle) -> Result<HealthSummary, String> {
+    let (tracked, operation, maintenance) = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let process = backend_state.child.lock().unwrap();
+        let tracked = process
+            .as_ref()
+            .map(|running| (running.child.pid(), running.mode, running.port, running.started_at));
+        let operation = backend_state.operation.lock().unwrap().map(BackendOperation::label);
+        (tracked, operation, backend_state.maintenance.load(Ordering::Relaxed))
+    };
+
+    let port = match tracked {
+        Some((_, _, port, _)) => port,
+        None => current_backend_port(&app_handle)?,
+    };
+    let health_path = health::resolve_health_path(&config::current(&app_handle))?;
+
+    let liveness = async {
+        match tracked {
+            Some((pid, ..)) => tauri::async_runtime::spawn_blocking(move || processes::is_alive(pid))
+                .await
+                .unwrap_or(false),
+            None => false,
+        }
+    };
+    let (running, health) = tokio::join!(liveness, health::probe(port, &health_path));
+
+    let stderr_buffer: State<BackendStderr> = app_handle.state();
+    let (health_latency_ms, health_error) = match health {
+        Ok(elapsed) => (Some(elapsed.as_millis() as u64), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    Ok(HealthSummary {
+        running,
+        pid: tracked.map(|(pid, ..)| pid),
+        mode: tracked.map(|(_, mode, ..)| mode),
+        port,
+        operation,
+        maintenance,
+        uptime_ms: tracked.map(|(.., started_at)| started_at.elapsed().as_millis() as u64),
+        healthy: health_latency_ms.is_some(),
+        health_latency_ms,
+        health_error,
+        last_error: stderr_buffer.last_error(),
+    })
+}
+
+/// The settings actually in effect, with the source of the port, so support can answer
+/// "why is it using that port?" without reconstructing the precedence rules by hand
+#[tauri::command]
+async fn get_effective_config(app_handle: AppHandle) -> Result<EffectiveConfig, String> {
+    let config = config::current(&app_handle);
+    let (mode, mode_source) = resolve_backend_mode(&app_handle)?;
+    let is_bundled = mode.is_bundled();
+
+    let running_port = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let process = backend_state.child.lock().unwrap();
+        process.as_ref().map(|running| running.port)
+    };
+    let (port, port_source) = match running_port {
+        Some(port) => (port, "running backend"),
+        None => {
+            let env = config::env_var(port::PORT_ENV_VAR);
+            port::resolve_port_with_source(None, env.as_deref(), config.port, default_port(is_bundled))?
+        }
+    };
 
     let workspace_dir = get_workspace_dir(&app_handle, is_bundled)?;
-    workspace_dir
-        .to_str()
-        .ok_or_else(|| "Failed to convert workspace path to string".to_string())
-        .map(|s| s.to_string())
+    let probe_settings = health::resolve_probe_settings(&config)?;
+
+    Ok(EffectiveConfig {
+        mode: mode.as_str(),
+        mode_source,
+        host: health::BACKEND_HOST,
+        port,
+        port_source,
+        health_path: health::resolve_health_path(&config)?,
+        ready_timeout_ms: health::READY_TIMEOUT.as_millis() as u64,
+        health_retries: probe_settings.retries,
+        health_interval_ms: probe_settings.interval.as_millis() as u64,
+        workspace_dir: workspace_dir.to_string_lossy().into_owned(),
+        config_file: config::config_path(&app_handle)
+            .ok()
+            .map(|path| path.to_string_lossy().into_owned()),
+        show_window_on_ready: config.show_window_on_ready.unwrap_or(false),
+        autostart: config::autostart_enabled(&config)?,
+        profile: config.profile.clone(),
+        log_level: log::max_level().to_string(),
+    })
+}
+
+/// Result of `get_sidecar_info`
+#[derive(Debug, Clone, Serialize)]
+struct SidecarInfo {
+    /// Whether `shell().sidecar(...)` resolves; this is what selects production mode
+    resolves: bool,
+    /// Resolved binary path, next to the app executable
+    path: Option<String>,
+    /// Whether a file actually exists at `path`
+    exists: bool,
+    /// macOS only: the binary still has the download quarantine flag
+    quarantined: bool,
+    error: Option<String>,
+}
+
+/// Explain dev-vs-production detection without spawning anything
+#[tauri::command]
+async fn get_sidecar_info(app_handle: AppHandle) -> Result<SidecarInfo, String> {
+    match app_handle.shell().sidecar("sts-gui-backend") {
+        Ok(sidecar_cmd) => {
+            let path = PathBuf::from(Command::from(sidecar_cmd).get_program());
+            Ok(SidecarInfo {
+                resolves: true,
+                exists: path.is_file(),
+                quarantined: quarantine::is_quarantined(&path),
+                path: Some(path.to_string_lossy().into_owned()),
+                error: None,
+            })
+        }
+        Err(e) => Ok(SidecarInfo {
+            resolves: false,
+            path: None,
+            exists: false,
+            quarantined: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Path of the bundled backend binary, if the sidecar resolves
+fn sidecar_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let sidecar_cmd = app_handle.shell().sidecar("sts-gui-backend").ok()?;
+    Some(PathBuf::from(Command::from(sidecar_cmd).get_program()))
+}
+
+/// Check the bundled backend against the checksum embedded at build time, so a partial
+/// download or a modified binary is reported clearly instead of as a confusing spawn failure
+#[tauri::command]
+async fn verify_sidecar_integrity(app_handle: AppHandle) -> Result<integrity::IntegrityReport, BackendError> {
+    let path = sidecar_path(&app_handle)
+        .ok_or_else(|| BackendError::NotFound("the bundled backend was not found".to_string()))?;
+
+    tauri::async_runtime::spawn_blocking(move || integrity::verify(&path))
+        .await
+        .map_err(|e| BackendError::Io(e.to_string()))?
+}
+
+/// Check that the bundled backend was built for this machine's CPU, so a wrong download is
+/// reported before the spawn fails. Also run at startup in bundled mode, which emits
+/// `sidecar-architecture-problem` when it needs emulation or cannot run at all.
+#[tauri::command]
+async fn check_sidecar_architecture(
+    app_handle: AppHandle,
+) -> Result<architecture::ArchitectureReport, BackendError> {
+    let path = sidecar_path(&app_handle)
+        .ok_or_else(|| BackendError::NotFound("the bundled backend was not found".to_string()))?;
+
+    tauri::async_runtime::spawn_blocking(move || architecture::check(&path))
+        .await
+        .map_err(|e| BackendError::Io(e.to_string()))?
+}
+
+/// Remove the macOS quarantine flag from the bundled backend after the user confirms in a
+/// native dialog. Returns whether it was removed (`false` if declined or not quarantined).
+#[tauri::command]
+async fn clear_sidecar_quarantine(app_handle: AppHandle) -> Result<bool, BackendError> {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let path = sidecar_path(&app_handle)
+        .ok_or_else(|| BackendError::NotFound("the bundled backend was not found".to_string()))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        if !quarantine::is_quarantined(&path) {
+            return Ok(false);
+        }
+
+        // blocking_show must stay off the main thread, which spawn_blocking guarantees
+        let confirmed = app_handle
+            .dialog()
+            .message(format!(
+                "macOS has quarantined the bundled backend, which stops it from starting.\n\n\
+                 Remove the quarantine flag from {}?\n\n\
+                 Only do this if you downloaded Script to Speech from its official release page.",
+                path.display()
+            ))
+            .title("Allow the backend to run")
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancelCustom("Remove flag".to_string(), "Cancel".to_string()))
+            .blocking_show();
+        if !confirmed {
+            info!("User declined removing the sidecar quarantine flag");
+            return Ok(false);
+        }
+
+        quarantine::remove(&path).map(|()| true)
+    })
+    .await
+    .map_err(|e| BackendError::Io(e.to_string()))?
+}
+
+/// Backend processes other than the one we own, and the ports that were scanned
+fn find_untracked_backends(app_handle: &AppHandle) -> Result<(Vec<u16>, Vec<processes::BackendProcessInfo>), String> {
+    let owned_pid = {
+        let backend_state: State<BackendProcess> = app_handle.state();
+        let process = backend_state.child.lock().unwrap();
+        process.as_ref().map(|running| running.child.pid())
+    };
+
+    let mut ports = vec![current_backend_port(app_handle)?, DEV_PORT, PROD_PORT];
+    ports.sort_unstable();
+    ports.dedup();
+
+    let found = processes::find_backend_processes(&ports, owned_pid);
+    Ok((ports, found))
+}
+
+/// List backend processes running alongside the one we own (left over from a crash or
+/// started by hand), so the UI can warn and offer `kill_orphaned_backends`. Uses the same
+/// strict matching, so an empty list is the normal case.
+#[tauri::command]
+async fn detect_duplicate_backends(app_handle: AppHandle) -> Result<Vec<processes::BackendProcessInfo>, String> {
+    let (ports, duplicates) = find_untracked_backends(&app_handle)?;
+    for process in &duplicates {
+        warn!(
+            "Duplicate backend on port {}: PID {} `{}`",
+            process.port, process.pid, process.command_line
+        );
+    }
+    if duplicates.is_empty() {
+        debug!("No duplicate backend processes found on ports {:?}", ports);
+    }
+    Ok(duplicates)
+}
+
+/// One process handled by `kill_orphaned_backends`
+#[derive(Debug, Clone, Serialize)]
+struct OrphanKill {
+    process: processes::BackendProcessInfo,
+    killed: bool,
+}
+
+/// Last-resort cleanup after a bad crash: kill backend processes we no longer track.
+/// The UI should confirm with the user before calling this.
+#[tauri::command]
+async fn kill_orphaned_backends(app_handle: AppHandle) -> Result<Vec<OrphanKill>, String> {
+    let (ports, orphans) = find_untracked_backends(&app_handle)?;
+
+    if orphans.is_empty() {
+        info!("No orphaned backend processes found on ports {:?}", ports);
+    }
+
+    Ok(orphans
+        .into_iter()
+        .map(|process| {
+            info!("Matched orphaned backend: PID {} `{}`", process.pid, process.command_line);
+            let killed = processes::kill(process.pid);
+            if killed {
+                info!("Killed orphaned backend (PID: {})", process.pid);
+            } else {
+                warn!("Failed to kill orphaned backend (PID: {})", process.pid);
+            }
+            OrphanKill { process, killed }
+        })
+        .collect())
+}
+
+/// Persist a dev/prod override for testing both code paths on one machine.
+/// `None` restores sidecar detection. `STS_FORCE_MODE` still wins if set.
+/// Takes effect on the next start/restart.
+#[tauri::command]
+async fn set_backend_mode_override(app_handle: AppHandle, mode: Option<String>) -> Result<(), String> {
+    let mode = match mode {
+        Some(value) => Some(
+            BackendMode::parse(&value)
+                .ok_or_else(|| format!("Invalid backend mode {:?}: expected \"dev\" or \"prod\"", value))?,
+        ),
+        None => None,
+    };
+
+    config::update(&app_handle, |config| {
+        config.force_mode = mode.map(|mode| mode.as_str().to_string());
+    })?;
+    info!("Backend mode override set to {:?}", mode.map(BackendMode::as_str));
+    Ok(())
+}
+
+/// Persist whether the backend starts with the app; takes effect on the next launch.
+/// `STS_AUTOSTART` still overrides the saved value.
+#[tauri::command]
+async fn set_backend_autostart(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    config::update(&app_handle, |config| config.autostart = Some(enabled))?;
+    info!("Backend autostart set to {}", enabled);
+    Ok(())
+}
+
+/// Change Rust-side log verbosity without restarting, e.g. `debug` while reproducing a bug.
+/// The choice is persisted and reapplied at startup. The Python backend's log level is
+/// configured separately and is not affected.
+#[tauri::command]
+async fn set_log_verbosity(app_handle: AppHandle, level: String) -> Result<String, String> {
+    let level = logs::parse_level(&level)?;
+    logs::set_level(level);
+
+    let name = level.to_string().to_lowercase();
+    config::update(&app_handle, |config| config.log_level = Some(name.clone()))?;
+    Ok(name)
+}
+
+/// Recent captured output of one backend (`source` as in the `backend-log` event, e.g.
+/// `production:58735`), or of all of them interleaved when `source` is omitted.
+/// Each backend has its own bounded buffer.
+#[tauri::command]
+async fn get_logs(app_handle: AppHandle, source: Option<String>) -> Result<Vec<logs::BackendLogLine>, String> {
+    Ok(app_handle.state::<logs::SourceLogs>().lines(source.as_deref()))
+}
+
+/// Backend sources `get_logs` has output for
+#[tauri::command]
+async fn get_log_sources(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    Ok(app_handle.state::<logs::SourceLogs>().sources())
+}
+
+/// Write the log lines from `start_ms` to `end_ms` (ms since the Unix epoch) into
+/// `destination`, for when a user can say roughly when something went wrong. Covers the
+/// buffered backend stderr, plus the app's log files if `include_log_files` is set.
+/// Returns the number of lines exported.
+#[tauri::command]
+async fn export_logs_range(
+    app_handle: AppHandle,
+    start_ms: u64,
+    end_ms: u64,
+    destination: String,
+    include_log_files: Option<bool>,
+) -> Result<usize, BackendError> {
+    if start_ms > end_ms {
+        return Err(BackendError::InvalidArgument(format!(
+            "start {} is after end {}",
+            start_ms, end_ms
+        )));
+    }
+    let destination = PathBuf::from(destination);
+    check_output_file(&destination)?;
+
+    let log_dir = if include_log_files.unwrap_or(false) {
+        Some(
+            app_handle
+                .path()
+                .app_log_dir()
+                .map_err(|e| BackendError::Io(format!("failed to resolve log directory: {}", e)))?,
+        )
+    } else {
+        None
+    };
+    let buffered = app_handle.state::<BackendStderr>().lines_between(start_ms, end_ms);
+
+    let exported = tauri::async_runtime::spawn_blocking(move || {
+        logs::export_range(buffered, log_dir.as_deref(), start_ms, end_ms, &destination)
+    })
+    .await
+    .map_err(|e| BackendError::Io(e.to_string()))??;
+    info!("Exported {} log lines between {} and {}", exported, start_ms, end_ms);
+    Ok(exported)
+}
+
+/// Send app logs to the console, the log file or both. Applies immediately to everything
+/// logged, including backend output when it is captured (see `set_output_capture`;
+/// release builds only capture it once that is on). Persisted for later launches.
+/// The bundled app usually has no visible console, so `console` there hides the logs.
+#[tauri::command]
+async fn set_log_sink(app_handle: AppHandle, sink: String) -> Result<logs::LogSink, BackendError> {
+    let sink = logs::LogSink::parse(&sink)?;
+    config::update(&app_handle, |config| config.log_sink = Some(sink.as_str().to_string()))
+        .map_err(BackendError::Io)?;
+    logs::set_sink(sink);
+    Ok(sink)
+}
+
+/// Set how many rotated app log files are kept (1-100). Persisted, and applied when the
+/// app next starts, which is also when surplus older files are removed.
+#[tauri::command]
+async fn set_log_retention(app_handle: AppHandle, count: u32) -> Result<u32, BackendError> {
+    let count = logs::validate_retention(count)?;
+    config::update(&app_handle, |config| config.log_retention = Some(count)).map_err(BackendError::Io)?;
+    info!("Log retention set to {} files (applies after restarting the app)", count);
+    Ok(count)
+}
+
+/// Number of rotated app log files kept, as configured (default 5)
+#[tauri::command]
+async fn get_log_retention(app_handle: AppHandle) -> Result<u32, String> {
+    Ok(logs::resolve_retention(&config::current(&app_handle)))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .manage(BackendProcess(Mutex::new(None)))
-        .plugin(tauri_plugin_log::Builder::new().build())
+        // Must be registered first: a second launch exits here, before it can spawn a
+        // backend that would fight the first one for the port
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            handle_secondary_launch(app, args)
+        }))
+        .manage(InstanceState::default())
+        .manage(ConfigWatchState::default())
+        .manage(BackendProcess {
+            child: Mutex::new(None),
+            operation: Mutex::new(None),
+            startup_abort: Mutex::new(None),
+            benchmark: tokio::sync::Mutex::new(()),
+            generation_download: Mutex::new(None),
+            state_changed: tokio::sync::Notify::new(),
+            maintenance: AtomicBool::new(false),
+            last_port: Mutex::new(None),
+            port_resolution: Mutex::new(None),
+        })
+        .manage(BackendStderr::default())
+        .manage(logs::SourceLogs::default())
+        .manage(LatestJobProgress::default())
+        .manage(RequestMetrics::default())
+        .manage(api::QuotaCache::default())
+        .manage(api::ProxyTimeout::default())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_upload::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_opener::init())
+        // Geometry is saved on close/exit and restored when the window is created.
+        // Visibility is excluded: the window stays hidden until we decide to show it.
+        .plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_state_flags(StateFlags::all() & !StateFlags::VISIBLE)
+                .build(),
+        )
         .setup(|app| {
-            // Automatically start the backend server
+            // Load persisted settings before anything resolves ports or paths
+            let (app_config, config_repair) = config::load(app.handle());
+            // Registered here rather than on the builder so log retention can come from
+            // the config; anything logged before this point is not recorded
+            let log_sink = app_config.log_sink.as_deref().map(logs::LogSink::parse);
+            let sink = match log_sink {
+                Some(Ok(sink)) => sink,
+                _ => logs::LogSink::Both,
+            };
+            app.handle()
+                .plugin(logs::plugin(logs::resolve_retention(&app_config), sink))?;
+            if let Some(Err(e)) = &log_sink {
+                warn!("Ignoring configured log sink: {}", e);
+            }
+            if let Some(repair) = &config_repair {
+                warn!("Config was corrupt and has been reset to defaults: {:?}", repair);
+            }
+            if let Some(level) = app_config.log_level.as_deref() {
+                match logs::parse_level(level) {
+                    Ok(level) => logs::set_level(level),
+                    Err(e) => warn!("Ignoring configured log level: {}", e),
+                }
+            }
+            // Release builds keep backend output out of the log unless capture was switched on
+            let capture_output = app_config.capture_output.unwrap_or(cfg!(debug_assertions));
+            app.manage(ConfigState(Mutex::new(app_config)));
+            app.manage(LogPipeline::start(app.handle().clone(), capture_output));
+            app.manage(StartupHistory::load(app.handle()));
+            app.manage(CrashHistory::load(app.handle()));
+
+            #[cfg(unix)]
+            install_sigterm_handler(app.handle().clone());
+            install_resume_handler(app.handle().clone());
+
+            if let Err(e) = clamp_main_window_onscreen(app.handle()) {
+                warn!("Failed to check window position: {}", e);
+            }
+
+            // Cheap, and catches privacy-restricted setups, synced workspaces and wrong-architecture
+            // downloads before they fail confusingly later
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = start_backend(app_handle).await {
-                    error!("Failed to auto-start backend: {}", e);
+                if let Some(repair) = config_repair {
+                    if let Err(e) = app_handle.emit("config-repaired", &repair) {
+                        warn!("Failed to emit config-repaired event: {}", e);
+                    }
+                }
+                match check_permissions(app_handle.clone()).await {
+                    Ok(report) if !report.ok => {
+                        if let Err(e) = app_handle.emit("permissions-problem", &report) {
+                            warn!("Failed to emit permissions-problem event: {}", e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Permission preflight failed: {}", e),
+                }
+                if let Err(e) = get_workspace_info(app_handle.clone()).await {
+                    warn!("Workspace location check failed: {}", e);
+                }
+
+                // Dev mode runs the backend through uv, so there is no binary to inspect
+                if !resolve_backend_mode(&app_handle).is_ok_and(|(mode, _)| mode.is_bundled()) {
+                    return;
+                }
+                match check_sidecar_architecture(app_handle.clone()).await {
+                    Ok(report) if !report.compatible || report.emulated => {
+                        if let Err(e) = app_handle.emit("sidecar-architecture-problem", &report) {
+                            warn!("Failed to emit sidecar-architecture-problem event: {}", e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Sidecar architecture preflight failed: {}", e),
                 }
             });
 
+            let autostart = config::autostart_enabled(&config::current(app.handle())).unwrap_or_else(|e| {
+                warn!("{}; starting the backend anyway", e);
+                true
+            });
+
+            // The window starts hidden (tauri.conf.json); either show it now or once
+            // the backend is ready, with a fallback timer so it can't stay invisible.
+            // Without autostart nothing will become ready, so show it straight away.
+            if autostart && config::current(app.handle()).show_window_on_ready.unwrap_or(false) {
+                info!("Main window will be shown once the backend is ready");
+                let app_handle = app.handle().clone();
+                app.once_any("backend-ready", move |_| show_main_window(&app_handle));
+
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(WINDOW_SHOW_FALLBACK).await;
+                    show_main_window(&app_handle);
+                });
+            } else {
+                show_main_window(app.handle());
+            }
+
+            if autostart {
+                // Automatically start the backend server
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = start_backend(app_handle.clone(), None).await {
+                        error!("Failed to auto-start backend: {}", e);
+                        // Nothing will become ready; show the window so the error is visible
+                        show_main_window(&app_handle);
+                    }
+                });
+            } else {
+                // Stopped initial state: no backend is tracked and no operation is in flight,
+                // so status commands report "not running" until the UI calls start_backend
+                info!("Backend autostart is disabled; waiting for start_backend");
+            }
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             start_backend,
             stop_backend,
-            get_workspace_path
+            restart_backend,
+            get_workspace_path,
+            get_backend_port,
+            open_app_data_dir,
+            get_last_backend_error,
+            ping_backend,
+            measure_backend_latency,
+            prewarm_backend,
+            test_provider_credentials,
+            prepare_for_update,
+            get_effective_config,
+            get_sidecar_info,
+            kill_orphaned_backends,
+            set_backend_mode_override,
+            backend_request,
+            get_health_summary,
+            set_log_verbosity,
+            ensure_workspace_structure,
+            get_api_base_url,
+            migrate_workspace,
+            get_workspace_free_space,
+            run_backend_command,
+            get_backend_workers,
+            set_backend_workers,
+            get_job_progress,
+            validate_script_path,
+            abort_startup,
+            get_backend_listening_ports,
+            request_backend_dump,
+            switch_profile,
+            check_external_tools,
+            open_file,
+            clear_backend_cache,
+            set_backend_autostart,
+            get_backend_capabilities,
+            start_backend_async,
+            set_secret,
+            has_secret,
+            run_backend_benchmark,
+            get_backend_mode_label,
+            get_backend_audio_config,
+            test_workspace_write,
+            save_workspace,
+            list_workspaces,
+            switch_workspace,
+            estimate_job,
+            set_output_capture,
+            get_backend_launch_command,
+            await_backend_state,
+            relaunch_app,
+            clear_sidecar_quarantine,
+            get_startup_history,
+            quiesce_backend,
+            is_primary_instance,
+            set_backend_proxy,
+            check_provider_connectivity,
+            save_session,
+            load_session,
+            stream_generation_to_file,
+            cancel_generation_download,
+            verify_sidecar_integrity,
+            set_log_retention,
+            get_log_retention,
+            get_backend_effective_config,
+            set_maintenance_mode,
+            get_backend_metrics,
+            refresh_backend_providers,
+            get_provider_quota,
+            dry_run_script,
+            set_parent_death_shutdown,
+            export_logs_range,
+            check_permissions,
+            recover_backend,
+            get_crash_history,
+            set_log_sink,
+            check_sidecar_architecture,
+            get_workspace_info,
+            set_default_request_timeout,
+            get_backend_temp_usage,
+            clean_backend_temp,
+            check_backend_port,
+            capture_window_screenshot,
+            set_backend_resource_limits,
+            repair_config,
+            get_logs,
+            get_log_sources,
+            set_config_watch,
+            backend_supports,
+            get_backend_device,
+            set_backend_device,
+            stop_and_archive_logs,
+            get_port_resolution,
+            set_power_handling,
+            get_api_client_config,
+            detect_duplicate_backends,
+            set_output_dir,
+            cancel_all_jobs
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
-            match event {
-                tauri::RunEvent::Exit => {
-                    info!("App exiting, cleaning up backend process...");
-                    shutdown_backend(app_handle);
+            if let tauri::RunEvent::Exit = event {
+                info!("App exiting, cleaning up backend process...");
+                tauri::async_runtime::block_on(cancel_jobs_before_exit(app_handle));
+                shutdown_backend(app_handle);
+                // Blocking is fine here as the process is about to end; also covers exits with no backend running
+                if let Some(pipeline) = app_handle.try_state::<LogPipeline>() {
+                    pipeline.flush();
                 }
-                _ => {}
             }
         });
 }
@@ -0,0 +1,315 @@
+use log::info;
+use serde::Serialize;
+
+use crate::config::AppConfig;
+use crate::error::BackendError;
+
+/// Below this the Python interpreter and its imports may not even load
+const MIN_MEMORY_MB: u64 = 256;
+const MIN_CPU_SECONDS: u64 = 10;
+
+/// Upper bounds for `validate`: 16 TiB and a year, far above any real machine or job
+const MAX_MEMORY_MB: u64 = 16 * 1024 * 1024;
+const MAX_CPU_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+/// Grace between the soft CPU limit (SIGXCPU) and the hard one (SIGKILL)
+#[cfg(unix)]
+const CPU_HARD_LIMIT_GRACE_SECONDS: u64 = 5;
+
+/// How the OS ends a process over its CPU limit: SIGXCPU on Unix, exit code
+/// ERROR_NOT_ENOUGH_QUOTA when a Windows job terminates it
+#[cfg(unix)]
+const CPU_LIMIT_SIGNAL: Option<i32> = Some(libc::SIGXCPU);
+#[cfg(not(unix))]
+const CPU_LIMIT_SIGNAL: Option<i32> = None;
+#[cfg(windows)]
+const CPU_LIMIT_EXIT_CODE: Option<i32> = Some(1816);
+#[cfg(not(windows))]
+const CPU_LIMIT_EXIT_CODE: Option<i32> = None;
+
+/// Stderr markers of an allocation failing at the memory cap
+const OUT_OF_MEMORY_MARKERS: [&str; 3] = ["MemoryError", "Cannot allocate memory", "std::bad_alloc"];
+
+/// Caps applied to the backend process at spawn; `None` means unlimited (the default).
+///
+/// - Development backend on Unix: `setrlimit` before exec, inherited by the Python child.
+/// - Bundled backend on Linux: `prlimit` right after spawn. PyInstaller's bootloader unpacks
+///   before it starts the server, so the server process inherits the limits in practice.
+/// - Windows: a Job Object, which also covers the processes the backend starts.
+/// - macOS: only the CPU limit, and only for the development backend. macOS does not enforce
+///   address-space limits and the sidecar is spawned without a pre-exec hook.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ResourceLimits {
+    pub max_memory_mb: Option<u64>,
+    /// Total CPU time, not wall-clock time
+    pub max_cpu_seconds: Option<u64>,
+}
+
+/// Payload of the `backend-resource-limit` event, emitted alongside `backend-crashed`
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceLimitHit {
+    pub pid: u32,
+    /// `memory` or `cpu_time`
+    pub limit: &'static str,
+    pub limits: ResourceLimits,
+}
+
+impl ResourceLimits {
+    pub fn from_config(config: &AppConfig) -> Self {
+        ResourceLimits {
+            max_memory_mb: config.backend_max_memory_mb,
+            max_cpu_seconds: config.backend_max_cpu_seconds,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_memory_mb.is_none() && self.max_cpu_seconds.is_none()
+    }
+
+    pub fn validate(&self) -> Result<(), BackendError> {
+        if let Some(mb) = self.max_memory_mb.filter(|mb| *mb < MIN_MEMORY_MB) {
+            return Err(BackendError::InvalidArgument(format!(
+                "memory limit {} MB is too low for the backend to start (minimum {} MB)",
+                mb, MIN_MEMORY_MB
+            )));
+        }
+        if let Some(mb) = self.max_memory_mb.filter(|mb| *mb > MAX_MEMORY_MB) {
+            return Err(BackendError::InvalidArgument(format!(
+                "memory limit {} MB is out of range (maximum {} MB); clear it for no limit",
+                mb, MAX_MEMORY_MB
+            )));
+        }
+        if let Some(seconds) = self.max_cpu_seconds.filter(|seconds| *seconds < MIN_CPU_SECONDS) {
+            return Err(BackendError::InvalidArgument(format!(
+                "CPU time limit {} s is too low for the backend to start (minimum {} s)",
+                seconds, MIN_CPU_SECONDS
+            )));
+        }
+        if let Some(seconds) = self.max_cpu_seconds.filter(|seconds| *seconds > MAX_CPU_SECONDS) {
+            return Err(BackendError::InvalidArgument(format!(
+                "CPU time limit {} s is out of range (maximum {} s); clear it for no limit",
+                seconds, MAX_CPU_SECONDS
+            )));
+        }
+        Ok(())
+    }
+
+    /// Which limit most likely ended a backend that exited with `code`/`signal`
+    pub fn hit_by(&self, code: Option<i32>, signal: Option<i32>, recent_stderr: &[String]) -> Option<&'static str> {
+        let cpu_limit = (CPU_LIMIT_SIGNAL.is_some() && signal == CPU_LIMIT_SIGNAL)
+            || (CPU_LIMIT_EXIT_CODE.is_some() && code == CPU_LIMIT_EXIT_CODE);
+        if self.max_cpu_seconds.is_some() && cpu_limit {
+            return Some("cpu_time");
+        }
+        let out_of_memory = recent_stderr
+            .iter()
+            .any(|line| OUT_OF_MEMORY_MARKERS.iter().any(|marker| line.contains(marker)));
+        (self.max_memory_mb.is_some() && out_of_memory).then_some("memory")
+    }
+}
+
+/// The memory cap in bytes. Saturates rather than wraps, since limits read from a
+/// hand-edited config never went through `validate`.
+fn memory_bytes(mb: u64) -> u64 {
+    mb.saturating_mul(1024 * 1024)
+}
+
+/// The hard CPU limit that follows a soft limit of `seconds`, saturating like `memory_bytes`
+#[cfg(unix)]
+fn cpu_hard_limit(seconds: u64) -> u64 {
+    seconds.saturating_add(CPU_HARD_LIMIT_GRACE_SECONDS)
+}
+
+/// Install the limits on a command about to be spawned (development backend)
+#[cfg(unix)]
+pub fn apply_before_exec(command: &mut std::process::Command, limits: ResourceLimits) {
+    use std::os::unix::process::CommandExt;
+
+    if limits.is_unlimited() {
+        return;
+    }
+    let memory = limits.max_memory_mb.filter(|_| !cfg!(target_os = "macos"));
+    if limits.max_memory_mb.is_some() && memory.is_none() {
+        log::warn!("Backend memory limit is not enforced on macOS; only the CPU limit applies");
+    }
+    // SAFETY: runs between fork and exec, where only async-signal-safe calls are allowed;
+    // setrlimit is one and nothing here allocates
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(mb) = memory {
+                set_rlimit(libc::RLIMIT_AS, memory_bytes(mb), memory_bytes(mb))?;
+            }
+            if let Some(seconds) = limits.max_cpu_seconds {
+                set_rlimit(libc::RLIMIT_CPU, seconds, cpu_hard_limit(seconds))?;
+            }
+            Ok(())
+        });
+    }
+    info!("Backend resource limits: {:?}", limits);
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+type RlimitResource = libc::c_int;
+#[cfg(target_os = "linux")]
+type RlimitResource = libc::__rlimit_resource_t;
+
+#[cfg(unix)]
+fn set_rlimit(resource: RlimitResource, soft: u64, hard: u64) -> std::io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: soft as libc::rlim_t,
+        rlim_max: hard as libc::rlim_t,
+    };
+    // SAFETY: `limit` is a valid rlimit for the duration of the call
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Apply the limits to an already running process (bundled backend)
+pub fn apply_to_process(pid: u32, limits: ResourceLimits) -> Result<(), BackendError> {
+    if limits.is_unlimited() {
+        return Ok(());
+    }
+    apply_to_pid(pid, limits)?;
+    info!("Backend (PID: {}) resource limits: {:?}", pid, limits);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn apply_to_pid(pid: u32, limits: ResourceLimits) -> Result<(), BackendError> {
+    let set = |resource: RlimitResource, soft: u64, hard: u64| {
+        let limit = libc::rlimit {
+            rlim_cur: soft as libc::rlim_t,
+            rlim_max: hard as libc::rlim_t,
+        };
+        // SAFETY: `limit` is valid for the call and the old-limit pointer may be null
+        if unsafe { libc::prlimit(pid as libc::pid_t, resource, &limit, std::ptr::null_mut()) } != 0 {
+            return Err(BackendError::Io(format!(
+                "failed to limit backend (PID: {}): {}",
+                pid,
+                std::io::Error::last_os_error()
+            )));
+        }
+        Ok(())
+    };
+    if let Some(mb) = limits.max_memory_mb {
+        set(libc::RLIMIT_AS, memory_bytes(mb), memory_bytes(mb))?;
+    }
+    if let Some(seconds) = limits.max_cpu_seconds {
+        set(libc::RLIMIT_CPU, seconds, cpu_hard_limit(seconds))?;
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn apply_to_pid(pid: u32, limits: ResourceLimits) -> Result<(), BackendError> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_PROCESS_MEMORY, JOB_OBJECT_LIMIT_PROCESS_TIME,
+    };
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+    let os_error = |action: &str| {
+        BackendError::Io(format!(
+            "failed to {} for backend (PID: {}): {}",
+            action,
+            pid,
+            std::io::Error::last_os_error()
+        ))
+    };
+
+    // SAFETY: plain Win32 calls; every handle opened here is closed before returning.
+    // The job outlives its handle for as long as the backend (its member) runs.
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job.is_null() {
+            return Err(os_error("create a job object"));
+        }
+
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+        if let Some(mb) = limits.max_memory_mb {
+            info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+            info.ProcessMemoryLimit = usize::try_from(memory_bytes(mb)).unwrap_or(usize::MAX);
+        }
+        if let Some(seconds) = limits.max_cpu_seconds {
+            info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+            // In 100-nanosecond ticks
+            info.BasicLimitInformation.PerProcessUserTimeLimit =
+                i64::try_from(seconds.saturating_mul(10_000_000)).unwrap_or(i64::MAX);
+        }
+        let result = if SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        ) == 0
+        {
+            Err(os_error("set job limits"))
+        } else {
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            if process.is_null() {
+                Err(os_error("open the process"))
+            } else {
+                let assigned = AssignProcessToJobObject(job, process);
+                CloseHandle(process);
+                if assigned == 0 {
+                    Err(os_error("assign the job object"))
+                } else {
+                    Ok(())
+                }
+            }
+        };
+        CloseHandle(job);
+        result
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn apply_to_pid(_pid: u32, _limits: ResourceLimits) -> Result<(), BackendError> {
+    Err(BackendError::Unsupported(
+        "resource limits for the bundled backend are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_memory_mb: Option<u64>, max_cpu_seconds: Option<u64>) -> ResourceLimits {
+        ResourceLimits {
+            max_memory_mb,
+            max_cpu_seconds,
+        }
+    }
+
+    #[test]
+    fn validate_accepts_the_bounds_and_unlimited() {
+        assert!(limits(None, None).validate().is_ok());
+        assert!(limits(Some(MIN_MEMORY_MB), Some(MIN_CPU_SECONDS)).validate().is_ok());
+        assert!(limits(Some(MAX_MEMORY_MB), Some(MAX_CPU_SECONDS)).validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_values() {
+        for bad in [
+            limits(Some(MIN_MEMORY_MB - 1), None),
+            limits(Some(MAX_MEMORY_MB + 1), None),
+            limits(Some(u64::MAX), None),
+            limits(None, Some(MIN_CPU_SECONDS - 1)),
+            limits(None, Some(MAX_CPU_SECONDS + 1)),
+            limits(None, Some(u64::MAX)),
+        ] {
+            assert!(matches!(bad.validate(), Err(BackendError::InvalidArgument(_))), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn conversions_saturate_instead_of_overflowing() {
+        assert_eq!(memory_bytes(MAX_MEMORY_MB), MAX_MEMORY_MB * 1024 * 1024);
+        assert_eq!(memory_bytes(u64::MAX), u64::MAX);
+        #[cfg(unix)]
+        assert_eq!(cpu_hard_limit(u64::MAX), u64::MAX);
+    }
+}
@@ -0,0 +1,818 @@
+use log::warn;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+use crate::config::AppConfig;
+use crate::error::BackendError;
+use crate::progress;
+use crate::workspace;
+
+/// Number of recent stderr lines kept for crash reports
+const MAX_STDERR_LINES: usize = 200;
+
+/// Recent lines kept per backend source for `get_logs`, and how many sources are kept
+const MAX_SOURCE_LINES: usize = 1000;
+const MAX_SOURCES: usize = 8;
+
+/// Lines buffered between the pipe readers and the consumer before new ones are dropped
+const LOG_CHANNEL_CAPACITY: usize = 1024;
+
+/// How long `LogPipeline::flush` waits for queued lines before giving up
+const FLUSH_TIMEOUT: Duration = Duration::from_millis(500);
+const FLUSH_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How uvicorn reports a failed bind (POSIX errno text, then the Windows WSAEADDRINUSE text)
+const BIND_CONFLICT_MARKERS: [&str; 2] = [
+    "address already in use",
+    "only one usage of each socket address",
+];
+
+const TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+const CHAINED_TRACEBACK_MARKERS: [&str; 2] = [
+    "During handling of the above exception, another exception occurred:",
+    "The above exception was the direct cause of the following exception:",
+];
+
+/// Rotated app log files kept by default, and the range `set_log_retention` accepts
+pub const DEFAULT_LOG_RETENTION: u32 = 5;
+const LOG_RETENTION_RANGE: (u32, u32) = (1, 100);
+
+/// Size at which the app log file is rotated
+const LOG_MAX_FILE_SIZE: u128 = 10 * 1024 * 1024;
+
+/// Validate a rotated-file count from the frontend
+pub fn validate_retention(count: u32) -> Result<u32, BackendError> {
+    let (min, max) = LOG_RETENTION_RANGE;
+    if !(min..=max).contains(&count) {
+        return Err(BackendError::InvalidArgument(format!(
+            "log retention must be between {} and {} files, got {}",
+            min, max, count
+        )));
+    }
+    Ok(count)
+}
+
+/// Number of log files to keep: config value (clamped into range) or the default
+pub fn resolve_retention(config: &AppConfig) -> u32 {
+    let (min, max) = LOG_RETENTION_RANGE;
+    config
+        .log_retention
+        .map_or(DEFAULT_LOG_RETENTION, |count| count.clamp(min, max))
+}
+
+/// Where app log lines (including captured backend output) are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSink {
+    Console,
+    File,
+    Both,
+}
+
+impl LogSink {
+    pub fn parse(sink: &str) -> Result<Self, BackendError> {
+        match sink.trim().to_ascii_lowercase().as_str() {
+            "console" => Ok(LogSink::Console),
+            "file" => Ok(LogSink::File),
+            "both" => Ok(LogSink::Both),
+            _ => Err(BackendError::InvalidArgument(format!(
+                "invalid log sink {:?} (expected console, file or both)",
+                sink
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogSink::Console => "console",
+            LogSink::File => "file",
+            LogSink::Both => "both",
+        }
+    }
+
+    fn writes_console(self) -> bool {
+        self != LogSink::File
+    }
+
+    fn writes_file(self) -> bool {
+        self != LogSink::Console
+    }
+}
+
+/// Current sink, read by the logger's target filters on every line
+static LOG_SINK: AtomicU8 = AtomicU8::new(LogSink::Both as u8);
+
+fn current_sink() -> LogSink {
+    match LOG_SINK.load(Ordering::Relaxed) {
+        sink if sink == LogSink::Console as u8 => LogSink::Console,
+        sink if sink == LogSink::File as u8 => LogSink::File,
+        _ => LogSink::Both,
+    }
+}
+
+/// Held shared by the file target while it writes a line, and exclusively by `archive`
+/// while it copies and truncates the current file, so lines wait rather than land in between
+static LOG_FILE_GATE: RwLock<()> = RwLock::new(());
+
+/// Switch log sinks; takes effect from the next line, no restart needed
+pub fn set_sink(sink: LogSink) {
+    LOG_SINK.store(sink as u8, Ordering::Relaxed);
+    log::info!("Log sink set to {}", sink.as_str());
+}
+
+/// The app logger: writes to stdout and/or the log directory as `sink` says (switchable
+/// later with `set_sink`), rotating at `LOG_MAX_FILE_SIZE` and keeping the newest
+/// `retention` files. Older files are pruned when it starts.
+pub fn plugin(retention: u32, sink: LogSink) -> TauriPlugin<Wry> {
+    LOG_SINK.store(sink as u8, Ordering::Relaxed);
+    tauri_plugin_log::Builder::new()
+        .targets([
+            Target::new(TargetKind::Stdout).filter(|_| current_sink().writes_console()),
+            Target::new(TargetKind::LogDir { file_name: None })
+                .filter(|_| current_sink().writes_file())
+                // The formatter wraps the write itself, so the gate covers the whole line
+                .format(|out, message, _| {
+                    let _writing = LOG_FILE_GATE.read().unwrap_or_else(|e| e.into_inner());
+                    out.finish(format_args!("{}", message))
+                }),
+        ])
+        .max_file_size(LOG_MAX_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(retention as usize))
+        .build()
+}
+
+/// Parse a log verbosity name (`off`, `error`, `warn`, `info`, `debug`, `trace`)
+pub fn parse_level(level: &str) -> Result<log::LevelFilter, BackendError> {
+    level.trim().parse().map_err(|_| {
+        BackendError::InvalidArgument(format!(
+            "invalid log level {:?} (expected off, error, warn, info, debug or trace)",
+            level
+        ))
+    })
+}
+
+/// Change the Rust-side log filter at runtime. This does not affect the backend's own logging.
+pub fn set_level(level: log::LevelFilter) {
+    log::set_max_level(level);
+    log::info!("Log verbosity set to {}", level);
+}
+
+/// Which backend output stream a line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+struct LogLine {
+    source: Arc<str>,
+    stream: LogStream,
+    line: String,
+}
+
+/// One captured backend line; payload of `backend-log` and returned by `get_logs`
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendLogLine {
+    /// The backend that wrote it, as `<mode>:<port>` (e.g. `production:58735`)
+    pub source: Arc<str>,
+    pub stream: LogStream,
+    /// When it arrived, in ms since the Unix epoch
+    pub at_ms: u64,
+    pub line: String,
+}
+
+/// Recent output of each backend, in separate ring buffers so a noisy backend can't evict
+/// another's lines. Once `MAX_SOURCES` are known, the one that wrote last longest ago is
+/// dropped to make room.
+#[derive(Default)]
+pub struct SourceLogs(Mutex<HashMap<Arc<str>, VecDeque<BackendLogLine>>>);
+
+impl SourceLogs {
+    fn push(&self, line: BackendLogLine) {
+        let mut sources = self.0.lock().unwrap();
+        if !sources.contains_key(&line.source) && sources.len() >= MAX_SOURCES {
+            let stalest = sources
+                .iter()
+                .min_by_key(|(_, lines)| lines.back().map_or(0, |line| line.at_ms))
+                .map(|(source, _)| source.clone());
+            if let Some(stalest) = stalest {
+                sources.remove(&stalest);
+            }
+        }
+
+        let lines = sources.entry(line.source.clone()).or_default();
+        if lines.len() == MAX_SOURCE_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Buffered lines of `source`, or of every source interleaved by arrival, oldest first
+    pub fn lines(&self, source: Option<&str>) -> Vec<BackendLogLine> {
+        let sources = self.0.lock().unwrap();
+        let mut lines: Vec<BackendLogLine> = sources
+            .iter()
+            .filter(|(name, _)| source.is_none_or(|source| &***name == source))
+            .flat_map(|(_, lines)| lines.iter().cloned())
+            .collect();
+        lines.sort_by_key(|line| line.at_ms);
+        lines
+    }
+
+    /// Names of the sources with buffered lines
+    pub fn sources(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.0.lock().unwrap().keys().map(|name| name.to_string()).collect();
+        names.sort();
+        names
+    }
+}
+
+/// Single consumer for all captured backend output.
+///
+/// Pipe readers (one per stream, per spawn) only forward lines into a bounded channel;
+/// one long-lived thread fans them out to logging and the stderr buffer. When the channel
+/// is full, lines are dropped and counted rather than blocking the backend's writes.
+/// The stderr buffer and progress parsing always run; copying lines into the app log
+/// can be switched at runtime with `set_forwarding`.
+pub struct LogPipeline {
+    sender: SyncSender<LogLine>,
+    dropped: AtomicU64,
+    /// Lines accepted but not yet handled by the consumer
+    pending: Arc<AtomicUsize>,
+    /// Whether lines are also written to the app log
+    forward: Arc<AtomicBool>,
+}
+
+impl LogPipeline {
+    /// Spawn the consumer thread. Call once at startup and manage the result.
+    pub fn start(app_handle: AppHandle, forward_to_log: bool) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<LogLine>(LOG_CHANNEL_CAPACITY);
+        let pending = Arc::new(AtomicUsize::new(0));
+        let forward = Arc::new(AtomicBool::new(forward_to_log));
+
+        let consumer_pending = pending.clone();
+        let consumer_forward = forward.clone();
+        std::thread::spawn(move || {
+            let stderr_buffer = app_handle.state::<BackendStderr>();
+            let source_logs = app_handle.state::<SourceLogs>();
+            for entry in receiver {
+                let tagged = BackendLogLine {
+                    source: entry.source.clone(),
+                    stream: entry.stream,
+                    at_ms: now_ms(),
+                    line: entry.line.clone(),
+                };
+                if let Err(e) = app_handle.emit("backend-log", &tagged) {
+                    warn!("Failed to emit backend-log event: {}", e);
+                }
+                source_logs.push(tagged);
+                match entry.stream {
+                    LogStream::Stdout => {
+                        if consumer_forward.load(Ordering::Relaxed) {
+                            log::debug!("[Backend stdout {}] {}", entry.source, entry.line);
+                        }
+                        if let Some(progress) = progress::parse(&entry.line) {
+                            progress::publish(&app_handle, progress);
+                        }
+                    }
+                    LogStream::Stderr => {
+                        if consumer_forward.load(Ordering::Relaxed) {
+                            log::warn!("[Backend stderr {}] {}", entry.source, entry.line);
+                        }
+                        stderr_buffer.push(&entry.line);
+                    }
+                }
+                consumer_pending.fetch_sub(1, Ordering::Release);
+            }
+        });
+
+        LogPipeline {
+            sender,
+            dropped: AtomicU64::new(0),
+            pending,
+            forward,
+        }
+    }
+
+    /// Start or stop copying backend output into the app log. Takes effect for the next
+    /// line; the backend's pipes stay open either way, so no restart is needed.
+    pub fn set_forwarding(&self, enabled: bool) {
+        self.forward.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Wait briefly for queued lines to be handled, then flush the logger's outputs.
+    /// Call before exit so the backend's last lines (often the crash cause) reach the log file.
+    /// Blocks the thread for up to `FLUSH_TIMEOUT`; async code should use `flush_async`.
+    pub fn flush(&self) {
+        let deadline = Instant::now() + FLUSH_TIMEOUT;
+        while self.pending.load(Ordering::Acquire) > 0 && Instant::now() < deadline {
+            std::thread::sleep(FLUSH_POLL_INTERVAL);
+        }
+        self.flush_logger();
+    }
+
+    /// `flush` for async callers: waits without tying up a runtime worker
+    pub async fn flush_async(&self) {
+        let deadline = Instant::now() + FLUSH_TIMEOUT;
+        while self.pending.load(Ordering::Acquire) > 0 && Instant::now() < deadline {
+            tokio::time::sleep(FLUSH_POLL_INTERVAL).await;
+        }
+        self.flush_logger();
+    }
+
+    fn flush_logger(&self) {
+        let remaining = self.pending.load(Ordering::Acquire);
+        if remaining > 0 {
+            warn!("{} backend log lines were not written before shutdown", remaining);
+        }
+        log::logger().flush();
+    }
+
+    /// Forward a line from backend `source` without blocking the caller
+    pub fn send(&self, source: &Arc<str>, stream: LogStream, line: String) {
+        // Count before sending so the consumer can never decrement first
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        let entry = LogLine {
+            source: source.clone(),
+            stream,
+            line,
+        };
+        match self.sender.try_send(entry) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.pending.fetch_sub(1, Ordering::AcqRel);
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped.is_power_of_two() {
+                    warn!("Backend log pipeline full; {} lines dropped so far", dropped);
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                self.pending.fetch_sub(1, Ordering::AcqRel);
+                warn!("Backend log pipeline consumer has stopped");
+            }
+        }
+    }
+}
+
+/// Upper bound on lines collected by one `BackendStderr` capture
+const MAX_CAPTURE_LINES: usize = 5000;
+
+/// Bounded buffer of the backend's most recent stderr lines.
+/// Kept separate from general logging so a crash cause is never evicted by stdout noise.
+#[derive(Default)]
+pub struct BackendStderr {
+    buffer: Mutex<StderrLines>,
+    /// While set, every pushed line is also collected here (e.g. for a stack dump,
+    /// which can be longer than the rolling buffer)
+    capture: Mutex<Option<Vec<String>>>,
+}
+
+/// Snapshot of the backend's last error output
+#[derive(Debug, Clone, Serialize)]
+pub struct LastBackendError {
+    /// The most recent complete Python traceback (including chained exceptions), if any
+    pub traceback: Option<String>,
+    /// Tail of stderr, oldest first
+    pub recent_stderr: Vec<String>,
+}
+
+#[derive(Default)]
+struct StderrLines {
+    /// Lines with the time they arrived (ms since the Unix epoch)
+    lines: VecDeque<(u64, String)>,
+    /// Lines pushed over the buffer's lifetime, evicted ones included
+    pushed: u64,
+    /// `pushed` when the current backend was spawned
+    spawn_start: u64,
+}
+
+impl StderrLines {
+    /// Buffered lines written by the current backend process
+    fn since_spawn(&self) -> impl Iterator<Item = &(u64, String)> {
+        let evicted = self.pushed - self.lines.len() as u64;
+        self.lines.iter().skip(self.spawn_start.saturating_sub(evicted) as usize)
+    }
+}
+
+impl BackendStderr {
+    /// Note that a new backend process is starting. Earlier lines stay available to
+    /// `last_error`, but checks about the current process (`has_bind_conflict`) skip them.
+    pub fn mark_spawn(&self) {
+        let mut buffer = self.buffer.lock().unwrap();
+        buffer.spawn_start = buffer.pushed;
+    }
+
+    pub fn push(&self, line: &str) {
+        let line = line.trim_end().to_string();
+        if let Some(capture) = self.capture.lock().unwrap().as_mut() {
+            if capture.len() < MAX_CAPTURE_LINES {
+                capture.push(line.clone());
+            }
+        }
+
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.lines.len() == MAX_STDERR_LINES {
+            buffer.lines.pop_front();
+        }
+        buffer.lines.push_back((now_ms(), line));
+        buffer.pushed += 1;
+    }
+
+    /// Buffered lines that arrived between `start_ms` and `end_ms` (inclusive)
+    pub fn lines_between(&self, start_ms: u64, end_ms: u64) -> Vec<(u64, String)> {
+        let buffer = self.buffer.lock().unwrap();
+        buffer
+            .lines
+            .iter()
+            .filter(|(at_ms, _)| (start_ms..=end_ms).contains(at_ms))
+            .cloned()
+            .collect()
+    }
+
+    /// Start collecting every stderr line until `finish_capture`
+    pub fn start_capture(&self) {
+        *self.capture.lock().unwrap() = Some(Vec::new());
+    }
+
+    /// Stop collecting and return what arrived since `start_capture`
+    pub fn finish_capture(&self) -> Vec<String> {
+        self.capture.lock().unwrap().take().unwrap_or_default()
+    }
+
+    /// Number of lines collected so far by the current capture
+    pub fn captured_len(&self) -> usize {
+        self.capture.lock().unwrap().as_ref().map_or(0, Vec::len)
+    }
+
+    /// Whether the current backend's stderr shows it failing to bind `port` because
+    /// something else holds it; output from earlier spawns is ignored
+    pub fn has_bind_conflict(&self, port: u16) -> bool {
+        let port = format!(", {})", port);
+        let buffer = self.buffer.lock().unwrap();
+        let conflict = buffer.since_spawn().any(|(_, line)| {
+            let line = line.to_lowercase();
+            line.contains(&port) && BIND_CONFLICT_MARKERS.iter().any(|marker| line.contains(marker))
+        });
+        conflict
+    }
+
+    pub fn last_error(&self) -> Option<LastBackendError> {
+        let buffer = self.buffer.lock().unwrap();
+        if buffer.lines.is_empty() {
+            return None;
+        }
+
+        let recent_stderr: Vec<String> = buffer.lines.iter().map(|(_, line)| line.clone()).collect();
+        Some(LastBackendError {
+            traceback: extract_last_traceback(&recent_stderr),
+            recent_stderr,
+        })
+    }
+}
+
+/// Find the last traceback block in `lines`, extended backwards over chained exceptions.
+fn extract_last_traceback(lines: &[String]) -> Option<String> {
+    let last = lines.iter().rposition(|line| line.starts_with(TRACEBACK_HEADER))?;
+    let mut start = last;
+
+    // Walk back through "During handling of the above exception..." chains
+    loop {
+        let marker = lines[..start]
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .filter(|&i| CHAINED_TRACEBACK_MARKERS.contains(&lines[i].trim()));
+        let previous = marker.and_then(|i| {
+            lines[..i]
+                .iter()
+                .rposition(|line| line.starts_with(TRACEBACK_HEADER))
+        });
+        match previous {
+            Some(i) => start = i,
+            None => break,
+        }
+    }
+
+    // Frames are indented; the exception line is the first unindented line after the header
+    let end = lines[last + 1..]
+        .iter()
+        .position(|line| !line.is_empty() && !line.starts_with(char::is_whitespace))
+        .map(|offset| last + 1 + offset)
+        .unwrap_or(lines.len() - 1);
+
+    Some(lines[start..=end].join("\n"))
+}
+
+/// Write log lines from `start_ms` to `end_ms` (ms since the Unix epoch, inclusive) into
+/// `destination`, oldest first, returning how many were written.
+///
+/// `buffered` are timestamped backend stderr lines. With `log_dir`, the app's log files
+/// are searched too; their lines carry `[YYYY-MM-DD][HH:MM:SS]` UTC prefixes (the log
+/// plugin's format), and unprefixed continuation lines belong to the entry above them.
+pub fn export_range(
+    buffered: Vec<(u64, String)>,
+    log_dir: Option<&Path>,
+    start_ms: u64,
+    end_ms: u64,
+    destination: &Path,
+) -> Result<usize, BackendError> {
+    let mut entries: Vec<(u64, String)> = buffered
+        .into_iter()
+        .map(|(at_ms, line)| (at_ms, format!("{}[backend stderr] {}", format_timestamp(at_ms), line)))
+        .collect();
+
+    if let Some(log_dir) = log_dir {
+        let mut files: Vec<_> = std::fs::read_dir(log_dir)
+            .map_err(|e| BackendError::Io(format!("failed to read log directory {:?}: {}", log_dir, e)))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "log"))
+            .collect();
+        files.sort();
+
+        // Log timestamps have whole seconds, so widen the start to the second it falls in
+        let start_second = start_ms - start_ms % 1000;
+        for file in files {
+            let contents = match std::fs::read_to_string(&file) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    warn!("Skipping log file {:?}: {}", file, e);
+                    continue;
+                }
+            };
+            let mut current = None;
+            for line in contents.lines() {
+                if let Some(at_ms) = parse_timestamp(line) {
+                    current = Some(at_ms);
+                }
+                if current.is_some_and(|at_ms| (start_second..=end_ms).contains(&at_ms)) {
+                    entries.push((current.unwrap_or_default(), line.to_string()));
+                }
+            }
+        }
+    }
+
+    // Stable, so lines logged within the same second keep their order
+    entries.sort_by_key(|(at_ms, _)| *at_ms);
+    let mut contents = entries
+        .iter()
+        .map(|(_, line)| line.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+    std::fs::write(destination, contents)
+        .map_err(|e| BackendError::Io(format!("failed to write {:?}: {}", destination, e)))?;
+    Ok(entries.len())
+}
+
+/// Longest label `archive` keeps in the folder name
+const MAX_ARCHIVE_LABEL_LEN: usize = 48;
+
+/// Result of `archive`
+#[derive(Debug, Clone, Serialize)]
+pub struct LogArchive {
+    pub path: String,
+    pub files: Vec<String>,
+}
+
+/// Move the app's log files from `log_dir` into a new `<UTC time>[-<label>]` folder under
+/// `archive_root`, leaving an empty current log behind.
+///
+/// Rotated files are closed and simply moved. The current file stays open in the logger,
+/// so it is copied and then truncated instead, holding `LOG_FILE_GATE` so lines logged
+/// meanwhile wait and land in the emptied file. The log sink is left alone.
+pub fn archive(log_dir: &Path, archive_root: &Path, label: Option<&str>) -> Result<LogArchive, BackendError> {
+    let label: String = label
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .take(MAX_ARCHIVE_LABEL_LEN)
+        .collect();
+    let stamp = archive_stamp(now_ms());
+    let name = if label.is_empty() { stamp } else { format!("{}-{}", stamp, label) };
+    let archive_dir = archive_root.join(name);
+    if archive_dir.exists() {
+        return Err(BackendError::InvalidArgument(format!("{:?} already exists", archive_dir)));
+    }
+    std::fs::create_dir_all(&archive_dir).map_err(|e| workspace::io_error("create", &archive_dir, e))?;
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(log_dir)
+        .map_err(|e| workspace::io_error("read", log_dir, e))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "log"))
+        .collect();
+    files.sort();
+    // The logger writes `<app name>.log`; rotated copies get a timestamp suffix
+    let current = files
+        .iter()
+        .filter(|path| path.is_file())
+        .min_by_key(|path| path.file_name().map_or(usize::MAX, |name| name.len()))
+        .cloned();
+
+    let mut archived = Vec::new();
+    for file in &files {
+        let Some(name) = file.file_name().filter(|_| Some(file) != current.as_ref()) else {
+            continue;
+        };
+        let target = archive_dir.join(name);
+        if std::fs::rename(file, &target).is_err() {
+            // Across volumes (the workspace may be elsewhere): copy, then delete
+            std::fs::copy(file, &target).map_err(|e| workspace::io_error("copy", file, e))?;
+            std::fs::remove_file(file).map_err(|e| workspace::io_error("delete", file, e))?;
+        }
+        archived.push(target.to_string_lossy().into_owned());
+    }
+
+    if let Some(current) = current {
+        let target = archive_dir.join(current.file_name().unwrap_or_default());
+        // Nothing may be logged while the gate is held: the line would wait for it forever
+        let moved = {
+            let _gate = LOG_FILE_GATE.write().unwrap_or_else(|e| e.into_inner());
+            std::fs::copy(&current, &target)
+                .map_err(|e| workspace::io_error("copy", &current, e))
+                .and_then(|_| {
+                    std::fs::OpenOptions::new()
+                        .write(true)
+                        .open(&current)
+                        .and_then(|file| file.set_len(0))
+                        .map_err(|e| workspace::io_error("truncate", &current, e))
+                })
+        };
+        moved?;
+        archived.push(target.to_string_lossy().into_owned());
+    }
+
+    log::info!("Archived {} log file(s) to {:?}", archived.len(), archive_dir);
+    Ok(LogArchive {
+        path: archive_dir.to_string_lossy().into_owned(),
+        files: archived,
+    })
+}
+
+/// `YYYYMMDD-HHMMSS` (UTC) for archive folder names
+fn archive_stamp(at_ms: u64) -> String {
+    let seconds = at_ms / 1000;
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let time = seconds % 86_400;
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Parse the `[YYYY-MM-DD][HH:MM:SS]` prefix the log plugin writes (UTC) into epoch ms
+fn parse_timestamp(line: &str) -> Option<u64> {
+    let prefix = line.get(..22)?;
+    let bytes = prefix.as_bytes();
+    if bytes[0] != b'[' || bytes[11] != b']' || bytes[12] != b'[' || bytes[21] != b']' {
+        return None;
+    }
+    let number = |range: std::ops::Range<usize>| prefix.get(range)?.parse::<u64>().ok();
+    let (year, month, day) = (number(1..5)?, number(6..8)?, number(9..11)?);
+    let (hour, minute, second) = (number(13..15)?, number(16..18)?, number(19..21)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let days = days_from_civil(year as i64, month as i64, day as i64);
+    let seconds = u64::try_from(days).ok()? * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(seconds * 1000)
+}
+
+/// The log plugin's timestamp prefix for `at_ms`
+fn format_timestamp(at_ms: u64) -> String {
+    let seconds = at_ms / 1000;
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let time = seconds % 86_400;
+    format!(
+        "[{:04}-{:02}-{:02}][{:02}:{:02}:{:02}]",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+// Gregorian date <-> days since 1970-01-01 (Howard Hinnant's algorithms)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIND_ERROR: &str =
+        "ERROR:    [Errno 98] error while attempting to bind on address ('127.0.0.1', 58735): address already in use";
+
+    #[test]
+    fn bind_conflict_from_current_spawn_is_detected() {
+        let stderr = BackendStderr::default();
+        stderr.mark_spawn();
+        stderr.push("INFO:     Started server process [1234]");
+        stderr.push(BIND_ERROR);
+        assert!(stderr.has_bind_conflict(58735));
+        assert!(!stderr.has_bind_conflict(58736));
+    }
+
+    #[test]
+    fn bind_conflict_from_earlier_spawn_is_ignored() {
+        let stderr = BackendStderr::default();
+        stderr.mark_spawn();
+        stderr.push(BIND_ERROR);
+        stderr.mark_spawn();
+        stderr.push("RuntimeError: provider failed to load");
+        assert!(!stderr.has_bind_conflict(58735));
+        // Still shown as the last error output
+        assert!(stderr.last_error().unwrap().recent_stderr.iter().any(|line| line == BIND_ERROR));
+    }
+
+    #[test]
+    fn spawn_mark_survives_eviction() {
+        let stderr = BackendStderr::default();
+        stderr.push(BIND_ERROR);
+        stderr.mark_spawn();
+        for i in 0..MAX_STDERR_LINES + 10 {
+            stderr.push(&format!("line {}", i));
+        }
+        assert!(!stderr.has_bind_conflict(58735));
+        stderr.push(BIND_ERROR);
+        assert!(stderr.has_bind_conflict(58735));
+    }
+
+    #[test]
+    fn archive_waits_for_a_line_being_written() {
+        use std::io::Write;
+
+        let root = std::env::temp_dir().join(format!("sts-log-archive-{}", std::process::id()));
+        let log_dir = root.join("logs");
+        std::fs::create_dir_all(&log_dir).unwrap();
+        let current = log_dir.join("app.log");
+        std::fs::write(&current, "first\n").unwrap();
+        std::fs::write(log_dir.join("app_2026-01-01_00-00-00.log"), "rotated\n").unwrap();
+        let sink = current_sink();
+
+        // Stands in for the file target in the middle of writing a line
+        let writing = LOG_FILE_GATE.read().unwrap();
+        let archiver = {
+            let (log_dir, archive_root) = (log_dir.clone(), root.join("archive"));
+            std::thread::spawn(move || archive(&log_dir, &archive_root, Some("test")))
+        };
+        std::thread::sleep(Duration::from_millis(100));
+        let mut file = std::fs::OpenOptions::new().append(true).open(&current).unwrap();
+        file.write_all(b"second\n").unwrap();
+        drop(writing);
+
+        let archived = archiver.join().unwrap().unwrap();
+        let copy = Path::new(&archived.path).join("app.log");
+        assert_eq!(std::fs::read_to_string(copy).unwrap(), "first\nsecond\n");
+        assert_eq!(std::fs::read_to_string(&current).unwrap(), "");
+        assert_eq!(archived.files.len(), 2);
+        assert_eq!(current_sink(), sink);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
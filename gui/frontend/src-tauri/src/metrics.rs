@@ -0,0 +1,218 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Distinct paths tracked before further ones are folded into `OVERFLOW_PATH`
+const MAX_TRACKED_PATHS: usize = 200;
+const OVERFLOW_PATH: &str = "(other)";
+
+/// Per-path request accounting for `backend_request`, read by `get_backend_metrics`.
+///
+/// Recording a request is a shared read lock on the path table plus atomic adds; the write
+/// lock is only taken the first time a path is seen and on reset. Paths are normalized
+/// (query dropped, id-like segments collapsed to `{id}`) so task and file ids don't grow
+/// the table without bound.
+pub struct RequestMetrics {
+    paths: RwLock<HashMap<String, Arc<PathCounters>>>,
+    since: Mutex<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct PathCounters {
+    requests: AtomicU64,
+    /// Transport failures and 5xx responses
+    errors: AtomicU64,
+    client_errors: AtomicU64,
+    total_latency_ms: AtomicU64,
+    max_latency_ms: AtomicU64,
+}
+
+/// Summary for one normalized path
+#[derive(Debug, Clone, Serialize)]
+pub struct PathMetrics {
+    pub path: String,
+    pub requests: u64,
+    pub errors: u64,
+    pub client_errors: u64,
+    pub error_rate: f64,
+    pub avg_latency_ms: u64,
+    pub max_latency_ms: u64,
+}
+
+/// Payload of `get_backend_metrics`
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSnapshot {
+    /// How long these counters have been collecting (since startup or the last reset)
+    pub window_ms: u64,
+    pub requests: u64,
+    pub errors: u64,
+    /// Busiest paths first
+    pub paths: Vec<PathMetrics>,
+}
+
+/// How a proxied request ended
+pub enum Outcome {
+    Status(u16),
+    Failed,
+}
+
+impl Default for RequestMetrics {
+    fn default() -> Self {
+        RequestMetrics {
+            paths: RwLock::new(HashMap::new()),
+            since: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl RequestMetrics {
+    pub fn record(&self, method: &str, path: &str, outcome: Outcome, latency: Duration) {
+        let key = format!("{} {}", method.to_ascii_uppercase(), normalize_path(path));
+        let latency_ms = latency.as_millis() as u64;
+
+        let counters = self.counters(key);
+        counters.requests.fetch_add(1, Ordering::Relaxed);
+        match outcome {
+            Outcome::Status(status) if status >= 500 => counters.errors.fetch_add(1, Ordering::Relaxed),
+            Outcome::Status(status) if status >= 400 => counters.client_errors.fetch_add(1, Ordering::Relaxed),
+            Outcome::Status(_) => 0,
+            Outcome::Failed => counters.errors.fetch_add(1, Ordering::Relaxed),
+        };
+        counters.total_latency_ms.fetch_add(latency_ms, Ordering::Relaxed);
+        counters.max_latency_ms.fetch_max(latency_ms, Ordering::Relaxed);
+    }
+
+    /// Counters for `key`, added on first use (or `OVERFLOW_PATH` once the table is full)
+    fn counters(&self, key: String) -> Arc<PathCounters> {
+        if let Some(counters) = self.paths.read().unwrap().get(&key) {
+            return counters.clone();
+        }
+        let mut paths = self.paths.write().unwrap();
+        let key = if paths.len() >= MAX_TRACKED_PATHS && !paths.contains_key(&key) {
+            OVERFLOW_PATH.to_string()
+        } else {
+            key
+        };
+        paths.entry(key).or_default().clone()
+    }
+
+    /// Current totals, optionally starting a fresh window afterwards. A request being
+    /// recorded at the very moment of a reset may be left out of both windows.
+    pub fn snapshot(&self, reset: bool) -> MetricsSnapshot {
+        let paths = if reset {
+            std::mem::take(&mut *self.paths.write().unwrap())
+        } else {
+            self.paths.read().unwrap().clone()
+        };
+        let window_ms = {
+            let mut since = self.since.lock().unwrap();
+            let window_ms = since.elapsed().as_millis() as u64;
+            if reset {
+                *since = Instant::now();
+            }
+            window_ms
+        };
+
+        let mut summaries: Vec<PathMetrics> = paths
+            .iter()
+            .filter_map(|(path, counters)| {
+                let requests = counters.requests.load(Ordering::Relaxed);
+                let errors = counters.errors.load(Ordering::Relaxed);
+                (requests > 0).then(|| PathMetrics {
+                    path: path.clone(),
+                    requests,
+                    errors,
+                    client_errors: counters.client_errors.load(Ordering::Relaxed),
+                    error_rate: errors as f64 / requests as f64,
+                    avg_latency_ms: counters.total_latency_ms.load(Ordering::Relaxed) / requests,
+                    max_latency_ms: counters.max_latency_ms.load(Ordering::Relaxed),
+                })
+            })
+            .collect();
+        summaries.sort_by(|a, b| b.requests.cmp(&a.requests).then_with(|| a.path.cmp(&b.path)));
+
+        MetricsSnapshot {
+            window_ms,
+            requests: summaries.iter().map(|path| path.requests).sum(),
+            errors: summaries.iter().map(|path| path.errors).sum(),
+            paths: summaries,
+        }
+    }
+}
+
+/// Drop the query string and collapse segments carrying digits (task ids, file names)
+fn normalize_path(path: &str) -> String {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    path.split('/')
+        .map(|segment| {
+            if segment.chars().any(|c| c.is_ascii_digit()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_outcomes_per_normalized_path() {
+        let metrics = RequestMetrics::default();
+        let ms = Duration::from_millis;
+        metrics.record("get", "/api/audiobook/status/3f2a9?verbose=1", Outcome::Status(200), ms(10));
+        metrics.record("GET", "/api/audiobook/status/77b1", Outcome::Status(404), ms(30));
+        metrics.record("GET", "/api/audiobook/status/77b1", Outcome::Status(503), ms(20));
+        metrics.record("POST", "/api/generate", Outcome::Failed, ms(5));
+
+        let snapshot = metrics.snapshot(false);
+        assert_eq!((snapshot.requests, snapshot.errors), (4, 2));
+        let status = &snapshot.paths[0];
+        assert_eq!(status.path, "GET /api/audiobook/status/{id}");
+        assert_eq!((status.requests, status.errors, status.client_errors), (3, 1, 1));
+        assert_eq!((status.avg_latency_ms, status.max_latency_ms), (20, 30));
+        assert_eq!(snapshot.paths[1].path, "POST /api/generate");
+    }
+
+    #[test]
+    fn reset_starts_an_empty_window() {
+        let metrics = RequestMetrics::default();
+        metrics.record("GET", "/health", Outcome::Status(200), Duration::ZERO);
+        assert_eq!(metrics.snapshot(true).requests, 1);
+        assert_eq!(metrics.snapshot(false).requests, 0);
+        assert!(metrics.snapshot(false).paths.is_empty());
+    }
+
+    #[test]
+    fn paths_beyond_the_limit_are_folded_together() {
+        let metrics = RequestMetrics::default();
+        for i in 0..MAX_TRACKED_PATHS + 5 {
+            let path = format!("/api/{}", "x".repeat(i + 1));
+            metrics.record("GET", &path, Outcome::Status(200), Duration::ZERO);
+        }
+        let snapshot = metrics.snapshot(false);
+        assert_eq!(snapshot.paths.len(), MAX_TRACKED_PATHS + 1);
+        let other = snapshot.paths.iter().find(|path| path.path == OVERFLOW_PATH).unwrap();
+        assert_eq!(other.requests, 5);
+    }
+
+    #[test]
+    fn concurrent_records_are_all_counted() {
+        let metrics = RequestMetrics::default();
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        metrics.record("GET", "/health", Outcome::Status(200), Duration::ZERO);
+                    }
+                });
+            }
+        });
+        assert_eq!(metrics.snapshot(false).requests, 8000);
+    }
+}
@@ -0,0 +1,154 @@
+use log::warn;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+use crate::error::BackendError;
+
+/// Lifecycle operation currently in flight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendOperation {
+    Starting,
+    Restarting,
+    Stopping,
+    Migrating,
+    ClearingCache,
+    CleaningTemp,
+    SwitchingWorkspace,
+}
+
+impl BackendOperation {
+    pub fn label(self) -> &'static str {
+        match self {
+            BackendOperation::Starting => "start",
+            BackendOperation::Restarting => "restart",
+            BackendOperation::Stopping => "stop",
+            BackendOperation::Migrating => "workspace migration",
+            BackendOperation::ClearingCache => "cache clearing",
+            BackendOperation::CleaningTemp => "temp cleanup",
+            BackendOperation::SwitchingWorkspace => "workspace switch",
+        }
+    }
+}
+
+/// Marks a lifecycle operation as in progress for as long as it is alive
+pub struct OperationGuard<'a> {
+    slot: &'a Mutex<Option<BackendOperation>>,
+    changed: &'a Notify,
+}
+
+impl<'a> OperationGuard<'a> {
+    /// Claim `slot` for `op`, failing if another operation already holds it.
+    /// `changed` is notified when the slot is claimed and again when it is released.
+    pub fn begin(
+        slot: &'a Mutex<Option<BackendOperation>>,
+        changed: &'a Notify,
+        op: BackendOperation,
+    ) -> Result<Self, BackendError> {
+        let mut current = slot.lock().unwrap();
+        if let Some(active) = *current {
+            warn!("Rejecting backend {}: a {} is already in progress", op.label(), active.label());
+            return Err(BackendError::OperationInProgress(format!(
+                "cannot {} the backend while a {} is in progress",
+                op.label(),
+                active.label()
+            )));
+        }
+        *current = Some(op);
+        drop(current);
+        changed.notify_waiters();
+        Ok(OperationGuard { slot, changed })
+    }
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        *self.slot.lock().unwrap() = None;
+        self.changed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+
+    #[test]
+    fn overlapping_operations_have_exactly_one_winner() {
+        let slot = Mutex::new(None);
+        let changed = Notify::new();
+        let attempted = Barrier::new(2);
+
+        let results: Vec<Result<BackendOperation, String>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = [BackendOperation::Starting, BackendOperation::Stopping]
+                .into_iter()
+                .map(|op| {
+                    let (slot, changed, attempted) = (&slot, &changed, &attempted);
+                    scope.spawn(move || {
+                        let guard = OperationGuard::begin(slot, changed, op);
+                        // Keep the winner's guard alive until both threads have tried
+                        attempted.wait();
+                        match guard {
+                            Ok(_guard) => Ok(op),
+                            Err(BackendError::OperationInProgress(message)) => Err(message),
+                            Err(e) => panic!("unexpected error: {:?}", e),
+                        }
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1, "{:?}", results);
+        let busy = results.iter().find_map(|result| result.as_ref().err()).unwrap();
+        assert!(busy.contains("in progress"), "{}", busy);
+        assert_eq!(*slot.lock().unwrap(), None, "winner's guard must release the slot on drop");
+    }
+
+    #[test]
+    fn busy_error_names_both_operations() {
+        let slot = Mutex::new(None);
+        let changed = Notify::new();
+        let _restart = OperationGuard::begin(&slot, &changed, BackendOperation::Restarting).unwrap();
+
+        match OperationGuard::begin(&slot, &changed, BackendOperation::Starting) {
+            Err(BackendError::OperationInProgress(message)) => {
+                assert_eq!(message, "cannot start the backend while a restart is in progress")
+            }
+            other => panic!("expected OperationInProgress, got {:?}", other.map(|_| ())),
+        }
+        assert_eq!(*slot.lock().unwrap(), Some(BackendOperation::Restarting));
+    }
+
+    #[test]
+    fn slot_is_free_again_after_drop() {
+        let slot = Mutex::new(None);
+        let changed = Notify::new();
+
+        let guard = OperationGuard::begin(&slot, &changed, BackendOperation::Stopping).unwrap();
+        assert_eq!(*slot.lock().unwrap(), Some(BackendOperation::Stopping));
+        drop(guard);
+        assert_eq!(*slot.lock().unwrap(), None);
+
+        let _next = OperationGuard::begin(&slot, &changed, BackendOperation::Starting).unwrap();
+    }
+
+    #[test]
+    fn claiming_and_releasing_notify_waiters() {
+        let slot = Mutex::new(None);
+        let changed = Notify::new();
+
+        tauri::async_runtime::block_on(async {
+            let claimed = changed.notified();
+            tokio::pin!(claimed);
+            claimed.as_mut().enable();
+            let guard = OperationGuard::begin(&slot, &changed, BackendOperation::Migrating).unwrap();
+            claimed.await;
+
+            let released = changed.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+            drop(guard);
+            released.await;
+        });
+    }
+}
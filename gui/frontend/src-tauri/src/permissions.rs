@@ -0,0 +1,146 @@
+use log::warn;
+use serde::Serialize;
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::BackendError;
+use crate::health::BACKEND_HOST;
+use crate::workspace;
+
+/// How long the loopback connection probe may take
+const LOOPBACK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Home folders macOS guards with its Files and Folders privacy setting
+const PROTECTED_MACOS_FOLDERS: [&str; 3] = ["Desktop", "Documents", "Downloads"];
+
+/// One probed permission
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionCheck {
+    /// `workspace_write`, `app_data_write` or `loopback_network`
+    pub name: &'static str,
+    pub ok: bool,
+    /// What was probed, or why it failed
+    pub detail: String,
+    /// What the user can do about a failure
+    pub remedy: Option<String>,
+}
+
+/// Payload of `check_permissions` and the `permissions-problem` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionReport {
+    pub ok: bool,
+    pub checks: Vec<PermissionCheck>,
+}
+
+/// Probe what the app needs from the OS: writing the workspace and its own data folder,
+/// and connecting to the backend over loopback. `app_data_dir` is the resolution error when
+/// the data folder couldn't be located, which fails that check. Blocking; run off the main thread.
+pub fn check(workspace_dir: &Path, app_data_dir: Result<PathBuf, String>) -> PermissionReport {
+    let checks = vec![
+        check_write("workspace_write", workspace_dir),
+        match app_data_dir {
+            Ok(dir) => check_write("app_data_write", &dir),
+            Err(e) => PermissionCheck {
+                name: "app_data_write",
+                ok: false,
+                detail: e,
+                remedy: Some(
+                    "Check that your user profile folder exists and is accessible; settings can't be saved until it is"
+                        .to_string(),
+                ),
+            },
+        },
+        check_loopback(),
+    ];
+    for failed in checks.iter().filter(|check| !check.ok) {
+        warn!("Permission check {} failed: {}", failed.name, failed.detail);
+    }
+    PermissionReport {
+        ok: checks.iter().all(|check| check.ok),
+        checks,
+    }
+}
+
+/// Write-test `dir`, or the nearest existing ancestor when it hasn't been created yet
+fn check_write(name: &'static str, dir: &Path) -> PermissionCheck {
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.is_dir()) else {
+        return PermissionCheck {
+            name,
+            ok: false,
+            detail: format!("no part of {:?} exists", dir),
+            remedy: Some("Choose a different workspace location".to_string()),
+        };
+    };
+
+    match workspace::test_write(existing) {
+        Ok(_) => PermissionCheck {
+            name,
+            ok: true,
+            detail: format!("{:?} is writable", existing),
+            remedy: None,
+        },
+        Err(e) => {
+            let remedy = match e {
+                BackendError::PermissionDenied(_) => write_permission_remedy(existing),
+                BackendError::StorageFull(_) => "Free up disk space or choose a workspace on another drive".to_string(),
+                _ => "Check that the folder is on an available, writable drive".to_string(),
+            };
+            PermissionCheck {
+                name,
+                ok: false,
+                detail: e.to_string(),
+                remedy: Some(remedy),
+            }
+        }
+    }
+}
+
+fn write_permission_remedy(dir: &Path) -> String {
+    let protected = cfg!(target_os = "macos")
+        && dir.components().any(|component| {
+            PROTECTED_MACOS_FOLDERS
+                .iter()
+                .any(|folder| component.as_os_str() == *folder)
+        });
+    if protected {
+        "Allow Script to Speech in System Settings > Privacy & Security > Files and Folders \
+         (or Full Disk Access), or move the workspace out of Desktop/Documents/Downloads"
+            .to_string()
+    } else {
+        format!(
+            "Make {:?} writable for your user, or choose a different workspace",
+            dir
+        )
+    }
+}
+
+/// Listen on an ephemeral loopback port and connect to it, as the app does with the backend
+fn check_loopback() -> PermissionCheck {
+    let remedy = Some(
+        "Allow Script to Speech in your firewall or security software; it only talks to \
+         its own backend on 127.0.0.1"
+            .to_string(),
+    );
+    // The listener must stay open until the connection attempt is done
+    let result = TcpListener::bind((BACKEND_HOST, 0)).and_then(|listener| {
+        let address = listener.local_addr()?;
+        TcpStream::connect_timeout(&address, LOOPBACK_TIMEOUT)?;
+        Ok(address)
+    });
+
+    match result {
+        Ok(address) => PermissionCheck {
+            name: "loopback_network",
+            ok: true,
+            detail: format!("connected to {}", address),
+            remedy: None,
+        },
+        Err(e) => PermissionCheck {
+            name: "loopback_network",
+            ok: false,
+            detail: format!("loopback connection failed: {}", e),
+            remedy,
+        },
+    }
+}
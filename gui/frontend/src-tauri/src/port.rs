@@ -0,0 +1,168 @@
+use crate::config::{self, AppConfig};
+use crate::error::BackendError;
+
+/// Environment variable that overrides the backend port
+pub const PORT_ENV_VAR: &str = "STS_PORT";
+
+/// Ports below this need root on Linux and older macOS, and belong to system services elsewhere
+const FIRST_UNPRIVILEGED_PORT: u32 = 1024;
+
+/// Unprivileged ports on the Fetch standard's "bad port" list: the webview refuses to
+/// connect to them, so a backend there would start but never be reachable from the UI
+const BROWSER_BLOCKED_PORTS: [u16; 19] = [
+    1719, 1720, 1723, 2049, 3659, 4045, 4190, 5060, 5061, 6000, 6566, 6665, 6666, 6667, 6668, 6669,
+    6679, 6697, 10080,
+];
+
+/// Suggested in errors; the dynamic range is never assigned to well-known services
+const SAFE_PORT_HINT: &str = "choose a port between 49152 and 65535";
+
+/// Resolve the backend port from all supported sources.
+///
+/// Precedence: command argument > `STS_PORT` env var > config file > mode default.
+/// The first source that is set wins; an invalid value in that source is an error
+/// rather than silently falling through, so a typo never leads to a surprise port.
+pub fn resolve_port(
+    arg: Option<u32>,
+    env: Option<&str>,
+    config: Option<u32>,
+    default: u16,
+) -> Result<u16, BackendError> {
+    resolve_port_with_source(arg, env, config, default).map(|(port, _)| port)
+}
+
+/// Same as `resolve_port`, also naming the source the port came from
+pub fn resolve_port_with_source(
+    arg: Option<u32>,
+    env: Option<&str>,
+    config: Option<u32>,
+    default: u16,
+) -> Result<(u16, &'static str), BackendError> {
+    if let Some(port) = arg {
+        return validate_port(port, "command argument").map(|port| (port, "command argument"));
+    }
+
+    if let Some(raw) = env.map(str::trim).filter(|raw| !raw.is_empty()) {
+        let port = raw.parse::<u32>().map_err(|_| {
+            BackendError::InvalidPort(format!("{}={:?} is not a number", PORT_ENV_VAR, raw))
+        })?;
+        return validate_port(port, PORT_ENV_VAR).map(|port| (port, PORT_ENV_VAR));
+    }
+
+    if let Some(port) = config {
+        return validate_port(port, "config file").map(|port| (port, "config file"));
+    }
+
+    Ok((default, "default"))
+}
+
+/// Resolve the port for a backend launch using the live env and loaded config
+pub fn resolve_backend_port(
+    arg: Option<u32>,
+    config: &AppConfig,
+    default: u16,
+) -> Result<u16, BackendError> {
+    let env = config::env_var(PORT_ENV_VAR);
+    resolve_port(arg, env.as_deref(), config.port, default)
+}
+
+fn validate_port(port: u32, source: &str) -> Result<u16, BackendError> {
+    match u16::try_from(port) {
+        Ok(0) => Err(BackendError::InvalidPort(format!(
+            "port 0 from {} is not allowed (must be 1-65535)",
+            source
+        ))),
+        Ok(port) if u32::from(port) < FIRST_UNPRIVILEGED_PORT => Err(BackendError::InvalidPort(format!(
+            "port {} from {} is a privileged port (below {}) that the backend may not bind without \
+             administrator rights; {}",
+            port, source, FIRST_UNPRIVILEGED_PORT, SAFE_PORT_HINT
+        ))),
+        Ok(port) if BROWSER_BLOCKED_PORTS.contains(&port) => {
+            Err(BackendError::InvalidPort(format!(
+                "port {} from {} is blocked by the app's web view for security reasons, so the UI \
+                 could not reach the backend there; {}",
+                port, source, SAFE_PORT_HINT
+            )))
+        }
+        Ok(port) => Ok(port),
+        Err(_) => Err(BackendError::InvalidPort(format!(
+            "port {} from {} is out of range (must be 1-65535)",
+            port, source
+        ))),
+    }
+}
+
+/// Check a port the user is about to configure, with the same rules a launch applies
+pub fn check(port: u32) -> Result<u16, BackendError> {
+    validate_port(port, "the settings")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: u16 = 58735;
+
+    fn rejected(result: Result<(u16, &'static str), BackendError>) -> bool {
+        matches!(result, Err(BackendError::InvalidPort(_)))
+    }
+
+    #[test]
+    fn argument_wins_over_every_other_source() {
+        assert_eq!(
+            resolve_port_with_source(Some(50001), Some("50002"), Some(50003), DEFAULT).unwrap(),
+            (50001, "command argument")
+        );
+    }
+
+    #[test]
+    fn env_wins_over_config() {
+        assert_eq!(
+            resolve_port_with_source(None, Some(" 50002 "), Some(50003), DEFAULT).unwrap(),
+            (50002, PORT_ENV_VAR)
+        );
+    }
+
+    #[test]
+    fn blank_env_falls_through_to_config() {
+        assert_eq!(
+            resolve_port_with_source(None, Some("  "), Some(50003), DEFAULT).unwrap(),
+            (50003, "config file")
+        );
+    }
+
+    #[test]
+    fn default_when_nothing_is_set() {
+        assert_eq!(
+            resolve_port_with_source(None, None, None, DEFAULT).unwrap(),
+            (DEFAULT, "default")
+        );
+        assert_eq!(resolve_port(None, None, None, DEFAULT).unwrap(), DEFAULT);
+    }
+
+    #[test]
+    fn invalid_value_in_winning_source_is_an_error() {
+        // A bad argument must not fall through to the valid env value
+        assert!(rejected(resolve_port_with_source(Some(0), Some("50002"), None, DEFAULT)));
+        assert!(rejected(resolve_port_with_source(None, Some("abc"), Some(50003), DEFAULT)));
+        assert!(rejected(resolve_port_with_source(None, None, Some(0), DEFAULT)));
+    }
+
+    #[test]
+    fn rejects_zero_privileged_and_out_of_range_ports() {
+        for port in [0, 1, 80, 1023, 65536, 70000, u32::MAX] {
+            assert!(rejected(resolve_port_with_source(Some(port), None, None, DEFAULT)), "{}", port);
+            assert!(check(port).is_err(), "{}", port);
+        }
+        assert_eq!(check(1024).unwrap(), 1024);
+        assert_eq!(check(65535).unwrap(), 65535);
+    }
+
+    #[test]
+    fn rejects_browser_blocked_ports() {
+        for port in BROWSER_BLOCKED_PORTS {
+            assert!(rejected(resolve_port_with_source(Some(port.into()), None, None, DEFAULT)), "{}", port);
+        }
+        assert!(rejected(resolve_port_with_source(None, Some("6667"), None, DEFAULT)));
+    }
+}
@@ -0,0 +1,37 @@
+use log::info;
+use std::future::Future;
+use std::time::{Duration, SystemTime};
+
+/// How often the wall clock is sampled
+const TICK: Duration = Duration::from_secs(5);
+
+/// Wall-clock time passing beyond `TICK` that counts as a sleep rather than a busy moment
+const SLEEP_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Call `on_resume` with roughly how long the machine slept, each time it wakes up.
+///
+/// Neither Tauri nor its plugins report suspend/resume, so a wake is recognised by the
+/// wall clock jumping while this task's timer was frozen. That works the same on Linux,
+/// macOS and Windows, but only after the fact: suspend notifications (logind
+/// `PrepareForSleep`, `NSWorkspaceWillSleepNotification`, `WM_POWERBROADCAST`) are not
+/// hooked, so nothing runs before the machine goes to sleep. A large forward clock
+/// correction looks the same and is treated as a wake too.
+pub async fn watch_for_resume<F, Fut>(mut on_resume: F)
+where
+    F: FnMut(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut last = SystemTime::now();
+    loop {
+        tokio::time::sleep(TICK).await;
+        let now = SystemTime::now();
+        let elapsed = now.duration_since(last).unwrap_or_default();
+        last = now;
+
+        if elapsed > TICK + SLEEP_THRESHOLD {
+            let slept = elapsed - TICK;
+            info!("System resumed after about {}s asleep", slept.as_secs());
+            on_resume(slept).await;
+        }
+    }
+}
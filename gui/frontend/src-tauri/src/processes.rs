@@ -0,0 +1,266 @@
+use log::info;
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind};
+
+/// Command-line markers that identify our backend: the dev entry point and the sidecar
+const BACKEND_MARKERS: [&str; 2] = ["sts-gui-server", "sts-gui-backend"];
+
+/// A running process that matches our backend's signature
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub command_line: String,
+    pub port: u16,
+}
+
+/// Whether `pid` refers to a live process (zombies awaiting reaping count as exited)
+pub fn is_alive(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+    system
+        .process(pid)
+        .is_some_and(|process| !matches!(process.status(), ProcessStatus::Zombie | ProcessStatus::Dead))
+}
+
+/// Whether `root` (or a descendant) appears to be listening on `port`.
+/// Uses /proc on Linux; elsewhere falls back to whether the loopback port can be bound,
+/// which can't tell who holds it but is enough to show *something* is listening.
+pub fn is_listening_on(root: u32, port: u16) -> bool {
+    if listening_sockets(root).iter().any(|socket| socket.port == port) {
+        return true;
+    }
+    matches!(
+        std::net::TcpListener::bind((crate::health::BACKEND_HOST, port)),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse
+    )
+}
+
+/// A loopback port that is free right now, chosen by the OS
+pub fn free_port() -> std::io::Result<u16> {
+    let listener = std::net::TcpListener::bind((crate::health::BACKEND_HOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Find processes that look like our backend serving one of `ports`.
+///
+/// Matching is deliberately strict: the command line must contain a backend marker
+/// *and* pass `--port` with one of our ports, so unrelated Python servers never match.
+/// The GUI process itself is always excluded, as is the whole process tree rooted at
+/// `owned_pid` (in dev mode `uv run` spawns a Python child carrying the same markers).
+pub fn find_backend_processes(ports: &[u16], owned_pid: Option<u32>) -> Vec<BackendProcessInfo> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cmd(UpdateKind::Always),
+    );
+
+    let own_pid = std::process::id();
+    let mut matches: Vec<BackendProcessInfo> = system
+        .processes()
+        .values()
+        .filter(|process| process.pid().as_u32() != own_pid)
+        .filter(|process| !owned_pid.is_some_and(|owned| is_in_tree(&system, process.pid(), owned)))
+        .filter(|process| !matches!(process.status(), ProcessStatus::Zombie | ProcessStatus::Dead))
+        .filter_map(|process| {
+            let args: Vec<String> = process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+
+            let has_marker = args
+                .iter()
+                .any(|arg| BACKEND_MARKERS.iter().any(|marker| arg.contains(marker)));
+            let port = port_arg(&args).filter(|port| ports.contains(port))?;
+
+            has_marker.then(|| BackendProcessInfo {
+                pid: process.pid().as_u32(),
+                name: process.name().to_string_lossy().into_owned(),
+                command_line: args.join(" "),
+                port,
+            })
+        })
+        .collect();
+
+    matches.sort_by_key(|info| info.pid);
+    matches
+}
+
+/// Ask the backend's Python processes to dump all thread stacks to stderr (SIGUSR1, which
+/// the backend registers with `faulthandler`).
+///
+/// Normally only `root` is signalled: the sidecar's bootloader forwards signals to its Python
+/// child, and signalling both would dump twice. With `descendants_only`, everything below
+/// `root` is signalled instead, for dev mode where the root is `uv` rather than Python.
+/// Returns how many processes were signalled.
+#[cfg(unix)]
+pub fn signal_stack_dump(root: u32, descendants_only: bool) -> usize {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    system
+        .processes()
+        .values()
+        .filter(|process| {
+            let pid = process.pid().as_u32();
+            if descendants_only {
+                pid != root && is_in_tree(&system, process.pid(), root)
+            } else {
+                pid == root
+            }
+        })
+        .filter(|process| process.kill_with(sysinfo::Signal::User1).unwrap_or(false))
+        .count()
+}
+
+/// A TCP socket the backend is listening on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ListeningSocket {
+    pub pid: u32,
+    pub address: String,
+    pub port: u16,
+}
+
+/// Best-effort list of TCP sockets listened on by `root` and its descendants
+/// (dev mode runs the server in a child of `uv`). Only implemented on Linux, via /proc;
+/// other platforms return an empty list.
+pub fn listening_sockets(root: u32) -> Vec<ListeningSocket> {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    let pids: Vec<u32> = system
+        .processes()
+        .keys()
+        .filter(|pid| is_in_tree(&system, **pid, root))
+        .map(|pid| pid.as_u32())
+        .collect();
+
+    #[cfg(target_os = "linux")]
+    let mut sockets = proc_net::listening_sockets(&pids);
+    #[cfg(not(target_os = "linux"))]
+    let mut sockets: Vec<ListeningSocket> = {
+        let _ = pids;
+        Vec::new()
+    };
+
+    sockets.sort_by(|a, b| (a.port, &a.address).cmp(&(b.port, &b.address)));
+    sockets.dedup();
+    sockets
+}
+
+#[cfg(target_os = "linux")]
+mod proc_net {
+    use super::ListeningSocket;
+    use std::collections::HashMap;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    /// `st` value of a listening socket in /proc/net/tcp*
+    const TCP_LISTEN: &str = "0A";
+
+    pub fn listening_sockets(pids: &[u32]) -> Vec<ListeningSocket> {
+        // Socket inode -> owning pid, from each process's fd links ("socket:[12345]")
+        let mut owners: HashMap<u64, u32> = HashMap::new();
+        for &pid in pids {
+            let Ok(fds) = std::fs::read_dir(format!("/proc/{}/fd", pid)) else {
+                continue;
+            };
+            for fd in fds.flatten() {
+                let Ok(target) = std::fs::read_link(fd.path()) else {
+                    continue;
+                };
+                let inode = target
+                    .to_str()
+                    .and_then(|target| target.strip_prefix("socket:["))
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|inode| inode.parse().ok());
+                if let Some(inode) = inode {
+                    owners.insert(inode, pid);
+                }
+            }
+        }
+
+        ["/proc/net/tcp", "/proc/net/tcp6"]
+            .iter()
+            .filter_map(|table| std::fs::read_to_string(table).ok())
+            .flat_map(|contents| {
+                contents
+                    .lines()
+                    .skip(1)
+                    .filter_map(|line| parse_line(line, &owners))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn parse_line(line: &str, owners: &HashMap<u64, u32>) -> Option<ListeningSocket> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(3) != Some(&TCP_LISTEN) {
+            return None;
+        }
+        let pid = *owners.get(&fields.get(9)?.parse().ok()?)?;
+
+        let (address, port) = fields.get(1)?.split_once(':')?;
+        Some(ListeningSocket {
+            pid,
+            address: parse_address(address)?,
+            port: u16::from_str_radix(port, 16).ok()?,
+        })
+    }
+
+    /// Addresses are printed as native-endian 32-bit words in hex
+    fn parse_address(hex: &str) -> Option<String> {
+        let words: Vec<u32> = (0..hex.len() / 8)
+            .map(|i| u32::from_str_radix(&hex[i * 8..i * 8 + 8], 16).ok())
+            .collect::<Option<_>>()?;
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_ne_bytes()).collect();
+
+        match bytes.len() {
+            4 => Some(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?).to_string()),
+            16 => Some(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?).to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `pid` is `root` or one of its descendants
+fn is_in_tree(system: &System, pid: Pid, root: u32) -> bool {
+    let mut current = Some(pid);
+    // Bounded walk in case of a parent cycle from a stale snapshot
+    for _ in 0..64 {
+        match current {
+            Some(pid) if pid.as_u32() == root => return true,
+            Some(pid) => current = system.process(pid).and_then(|process| process.parent()),
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Kill `pid`, returning whether the signal was delivered
+pub fn kill(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+    match system.process(pid) {
+        Some(process) => {
+            info!("Killing PID {} ({})", pid, process.name().to_string_lossy());
+            process.kill()
+        }
+        None => false,
+    }
+}
+
+/// Extract the value of `--port N` / `--port=N` from a command line
+fn port_arg(args: &[String]) -> Option<u16> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == "--port" {
+            args.get(i + 1).and_then(|value| value.parse().ok())
+        } else {
+            arg.strip_prefix("--port=").and_then(|value| value.parse().ok())
+        }
+    })
+}
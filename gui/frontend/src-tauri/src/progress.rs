@@ -0,0 +1,56 @@
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Backend stdout lines starting with this prefix carry one JSON progress object, e.g.
+/// `STS_PROGRESS {"job_id": "abc", "progress": 0.5, "message": "Chunk 3 of 6"}`.
+/// The backend's audiobook generation service prints these (`PROGRESS_MARKER` in
+/// `audiobook_generation_service.py`) whenever a task's progress or message changes;
+/// keep the two sides in sync.
+pub const PROGRESS_MARKER: &str = "STS_PROGRESS ";
+
+/// Payload of `backend-job-progress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobProgress {
+    #[serde(default)]
+    pub job_id: Option<String>,
+    /// Fraction complete, 0.0 to 1.0
+    pub progress: f64,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Latest progress report, for UIs that subscribe after it was emitted
+#[derive(Default)]
+pub struct LatestJobProgress(Mutex<Option<JobProgress>>);
+
+impl LatestJobProgress {
+    pub fn get(&self) -> Option<JobProgress> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// Parse a progress marker line; anything else (including malformed markers) is `None`
+pub fn parse(line: &str) -> Option<JobProgress> {
+    let payload = line.trim().strip_prefix(PROGRESS_MARKER)?;
+    match serde_json::from_str::<JobProgress>(payload) {
+        Ok(mut progress) if progress.progress.is_finite() => {
+            progress.progress = progress.progress.clamp(0.0, 1.0);
+            Some(progress)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            debug!("Ignoring malformed progress marker {:?}: {}", payload, e);
+            None
+        }
+    }
+}
+
+/// Store `progress` as the latest snapshot and emit `backend-job-progress`
+pub fn publish(app_handle: &AppHandle, progress: JobProgress) {
+    *app_handle.state::<LatestJobProgress>().0.lock().unwrap() = Some(progress.clone());
+    if let Err(e) = app_handle.emit("backend-job-progress", &progress) {
+        warn!("Failed to emit backend-job-progress event: {}", e);
+    }
+}
@@ -0,0 +1,69 @@
+use log::{info, warn};
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::BackendError;
+
+/// Extended attribute macOS sets on downloaded files; Gatekeeper checks it on first launch
+pub const QUARANTINE_ATTR: &str = "com.apple.quarantine";
+
+/// Spawn errors Gatekeeper produces when it refuses to run a quarantined binary
+const DENIAL_MARKERS: [&str; 4] = [
+    "Operation not permitted",
+    "os error 1)",
+    "Permission denied",
+    "os error 13)",
+];
+
+/// Whether `binary` carries the quarantine attribute. Always `false` off macOS.
+pub fn is_quarantined(binary: &Path) -> bool {
+    if !cfg!(target_os = "macos") {
+        return false;
+    }
+    Command::new("xattr")
+        .args(["-p", QUARANTINE_ATTR])
+        .arg(binary)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// If a failed spawn of `binary` looks like a Gatekeeper/quarantine denial, explain how to
+/// fix it; otherwise `None` so the original error is shown
+pub fn explain_spawn_failure(binary: &Path, error: &str) -> Option<String> {
+    if !DENIAL_MARKERS.iter().any(|marker| error.contains(marker)) || !is_quarantined(binary) {
+        return None;
+    }
+
+    warn!("Sidecar {:?} is quarantined; spawn was likely blocked by Gatekeeper", binary);
+    Some(format!(
+        "macOS blocked the bundled backend because the app is still quarantined after download ({}). \
+         To fix this: move Script to Speech to the Applications folder, open it once with \
+         right-click > Open, or let the app remove the quarantine flag from the backend. \
+         You can also run: xattr -d {} {:?}",
+        error, QUARANTINE_ATTR, binary
+    ))
+}
+
+/// Remove the quarantine attribute from `binary`. Only call this after the user agreed.
+pub fn remove(binary: &Path) -> Result<(), BackendError> {
+    if !cfg!(target_os = "macos") {
+        return Err(BackendError::InvalidArgument("quarantine only applies on macOS".to_string()));
+    }
+
+    let output = Command::new("xattr")
+        .args(["-d", QUARANTINE_ATTR])
+        .arg(binary)
+        .output()
+        .map_err(|e| BackendError::Io(format!("failed to run xattr: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(if stderr.contains("Permission denied") {
+            BackendError::PermissionDenied(format!("cannot update {:?}: {}", binary, stderr.trim()))
+        } else {
+            BackendError::Io(format!("xattr -d failed for {:?}: {}", binary, stderr.trim()))
+        });
+    }
+
+    info!("Removed {} from {:?}", QUARANTINE_ATTR, binary);
+    Ok(())
+}
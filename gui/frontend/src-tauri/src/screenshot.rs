@@ -0,0 +1,106 @@
+use log::{debug, info};
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::BackendError;
+
+/// Screen area to capture, in physical pixels
+#[derive(Debug, Clone, Copy)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Physical pixels per logical point, for tools that take logical coordinates
+    pub scale_factor: f64,
+}
+
+/// Capture `region` of the screen into the PNG file `destination`.
+///
+/// Neither Tauri nor the system web views expose a capture API, so this shells out to the
+/// platform's screenshot tool: `screencapture` on macOS (needs the Screen Recording
+/// permission, or only the desktop background is captured), PowerShell with
+/// System.Drawing on Windows, and `grim` (Wayland) or ImageMagick's `import` (X11) on Linux.
+/// Anything covering the window at that moment is captured too.
+pub fn capture(region: Region, destination: &Path) -> Result<(), BackendError> {
+    let mut attempts = commands(region, destination);
+    let mut failures = Vec::new();
+    for command in attempts.iter_mut() {
+        let program = command.get_program().to_string_lossy().into_owned();
+        debug!("Capturing screenshot with {}", program);
+        match command.output() {
+            Ok(output) if output.status.success() && destination.is_file() => {
+                info!("Saved window screenshot to {:?}", destination);
+                return Ok(());
+            }
+            Ok(output) => failures.push(format!(
+                "{} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                failures.push(format!("{} is not installed", program))
+            }
+            Err(e) => failures.push(format!("failed to run {}: {}", program, e)),
+        }
+    }
+
+    Err(BackendError::Unsupported(format!(
+        "could not capture the window ({})",
+        failures.join("; ")
+    )))
+}
+
+/// Capture commands to try in order
+fn commands(region: Region, destination: &Path) -> Vec<Command> {
+    let Region {
+        x,
+        y,
+        width,
+        height,
+        scale_factor,
+    } = region;
+
+    if cfg!(target_os = "macos") {
+        // `-R` takes points; `-x` silences the shutter sound
+        let logical = |value: f64| (value / scale_factor).round() as i64;
+        let mut screencapture = Command::new("screencapture");
+        screencapture
+            .args(["-x", "-t", "png", "-R"])
+            .arg(format!(
+                "{},{},{},{}",
+                logical(x as f64),
+                logical(y as f64),
+                logical(width as f64),
+                logical(height as f64)
+            ))
+            .arg(destination);
+        vec![screencapture]
+    } else if cfg!(windows) {
+        let script = format!(
+            "Add-Type -AssemblyName System.Drawing; \
+             $bitmap = New-Object System.Drawing.Bitmap {width}, {height}; \
+             $graphics = [System.Drawing.Graphics]::FromImage($bitmap); \
+             $graphics.CopyFromScreen({x}, {y}, 0, 0, $bitmap.Size); \
+             $bitmap.Save($env:STS_SCREENSHOT_PATH, [System.Drawing.Imaging.ImageFormat]::Png); \
+             $graphics.Dispose(); $bitmap.Dispose()"
+        );
+        let mut powershell = Command::new("powershell");
+        powershell
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            // Passed through the environment so the path needs no PowerShell quoting
+            .env("STS_SCREENSHOT_PATH", destination);
+        vec![powershell]
+    } else {
+        let mut grim = Command::new("grim");
+        grim.arg("-g")
+            .arg(format!("{},{} {}x{}", x, y, width, height))
+            .arg(destination);
+        let mut import = Command::new("import");
+        import
+            .args(["-window", "root", "-crop"])
+            .arg(format!("{}x{}+{}+{}", width, height, x, y))
+            .arg(destination);
+        vec![grim, import]
+    }
+}
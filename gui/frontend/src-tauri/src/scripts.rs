@@ -0,0 +1,90 @@
+use serde::Serialize;
+use std::io::Read;
+use std::path::Path;
+
+/// Screenplay formats the backend can parse, used unless the config overrides them
+pub const DEFAULT_SCRIPT_EXTENSIONS: [&str; 2] = ["pdf", "txt"];
+
+/// Anything larger is almost certainly not a screenplay
+const MAX_SCRIPT_BYTES: u64 = 100 * 1024 * 1024;
+
+/// How much of the file is read to check that it is readable and looks like its type
+const SNIFF_BYTES: u64 = 8 * 1024;
+
+/// Result of `validate_script_path`
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptValidation {
+    pub valid: bool,
+    pub path: String,
+    pub extension: Option<String>,
+    pub size_bytes: Option<u64>,
+    /// Human-readable reasons the file was rejected; empty when valid
+    pub problems: Vec<String>,
+}
+
+/// Check a user-selected script file before handing it to the backend.
+/// Reads at most `SNIFF_BYTES`, so large or binary files can't stall the caller.
+pub fn validate(path: &Path, extensions: &[String]) -> ScriptValidation {
+    let mut problems = Vec::new();
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    match &extension {
+        Some(ext) if extensions.iter().any(|allowed| allowed.eq_ignore_ascii_case(ext)) => {}
+        _ => problems.push(format!(
+            "Unsupported file type; expected one of: {}",
+            extensions.join(", ")
+        )),
+    }
+
+    let size_bytes = match std::fs::metadata(path) {
+        Ok(metadata) if !metadata.is_file() => {
+            problems.push("Path is not a file".to_string());
+            None
+        }
+        Ok(metadata) => Some(metadata.len()),
+        Err(e) => {
+            problems.push(format!("File cannot be accessed: {}", e));
+            None
+        }
+    };
+
+    if let Some(size) = size_bytes {
+        if size == 0 {
+            problems.push("File is empty".to_string());
+        } else if size > MAX_SCRIPT_BYTES {
+            problems.push(format!(
+                "File is too large ({} MB, limit {} MB)",
+                size / (1024 * 1024),
+                MAX_SCRIPT_BYTES / (1024 * 1024)
+            ));
+        } else if let Err(problem) = sniff(path, extension.as_deref()) {
+            problems.push(problem);
+        }
+    }
+
+    ScriptValidation {
+        valid: problems.is_empty(),
+        path: path.to_string_lossy().into_owned(),
+        extension,
+        size_bytes,
+        problems,
+    }
+}
+
+/// Read the start of the file to confirm it is readable and plausibly of its stated type
+fn sniff(path: &Path, extension: Option<&str>) -> Result<(), String> {
+    let mut head = Vec::new();
+    std::fs::File::open(path)
+        .and_then(|file| file.take(SNIFF_BYTES).read_to_end(&mut head))
+        .map_err(|e| format!("File cannot be read: {}", e))?;
+
+    match extension {
+        Some("pdf") if !head.starts_with(b"%PDF-") => {
+            Err("File does not look like a PDF".to_string())
+        }
+        Some("txt") if head.contains(&0) => Err("File looks binary, not plain text".to_string()),
+        _ => Ok(()),
+    }
+}
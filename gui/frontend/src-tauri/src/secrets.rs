@@ -0,0 +1,78 @@
+use log::{debug, warn};
+
+use crate::error::BackendError;
+
+/// Keychain service name all entries are stored under
+const KEYRING_SERVICE: &str = "com.scripttospeech.desktop";
+
+/// Providers whose API key can live in the OS keychain, and the env var the backend reads
+/// it from (see each provider's tts_provider.py)
+const PROVIDER_KEY_ENV_VARS: [(&str, &str); 5] = [
+    ("cartesia", "CARTESIA_API_KEY"),
+    ("elevenlabs", "ELEVEN_API_KEY"),
+    ("minimax", "MINIMAX_API_KEY"),
+    ("openai", "OPENAI_API_KEY"),
+    ("zonos", "ZONOS_API_KEY"),
+];
+
+fn entry(provider: &str) -> Result<keyring::Entry, BackendError> {
+    if !PROVIDER_KEY_ENV_VARS
+        .iter()
+        .any(|(name, _)| *name == provider)
+    {
+        return Err(BackendError::InvalidArgument(format!(
+            "unknown provider {:?}",
+            provider
+        )));
+    }
+    keyring::Entry::new(KEYRING_SERVICE, provider)
+        .map_err(|e| BackendError::Io(format!("keychain unavailable: {}", e)))
+}
+
+/// Store `value` as the API key for `provider`; an empty value deletes it
+pub fn set(provider: &str, value: &str) -> Result<(), BackendError> {
+    let entry = entry(provider)?;
+    let value = value.trim();
+    let result = if value.is_empty() {
+        match entry.delete_credential() {
+            Err(keyring::Error::NoEntry) => Ok(()),
+            other => other,
+        }
+    } else {
+        entry.set_password(value)
+    };
+    result
+        .map_err(|e| BackendError::Io(format!("failed to update keychain for {}: {}", provider, e)))
+}
+
+/// Whether an API key for `provider` is stored. The value itself is never returned.
+pub fn has(provider: &str) -> Result<bool, BackendError> {
+    match entry(provider)?.get_password() {
+        Ok(_) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(BackendError::Io(format!(
+            "failed to read keychain for {}: {}",
+            provider, e
+        ))),
+    }
+}
+
+/// Env vars for every stored API key, for injecting into the backend at spawn.
+/// Keychain failures are logged and skipped so they never block startup.
+pub fn backend_env() -> Vec<(&'static str, String)> {
+    PROVIDER_KEY_ENV_VARS
+        .iter()
+        .filter_map(|(provider, env_var)| {
+            let entry = keyring::Entry::new(KEYRING_SERVICE, provider).ok()?;
+            match entry.get_password() {
+                Ok(value) => Some((*env_var, value)),
+                Err(keyring::Error::NoEntry) => None,
+                Err(e) => {
+                    warn!("Failed to read {} API key from keychain: {}", provider, e);
+                    None
+                }
+            }
+        })
+        .inspect(|(env_var, _)| debug!("Passing {} from keychain to backend", env_var))
+        .collect()
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::AppConfig;
+use crate::error::BackendError;
+use crate::progress::JobProgress;
+
+/// Bump when the snapshot layout changes incompatibly; older files are then rejected
+pub const SESSION_VERSION: u32 = 1;
+
+/// Snapshot of settings plus runtime choices (like a port picked for this run), written by
+/// `save_session` for bug reports or to resume work later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    /// App version that wrote the file, for bug reports
+    pub app_version: String,
+    pub saved_at_ms: u64,
+    pub mode: String,
+    /// Port the backend was running on, if it was
+    pub port: Option<u16>,
+    pub workspace_dir: String,
+    pub profile: Option<String>,
+    pub workers: Option<u32>,
+    pub last_job: Option<JobProgress>,
+    pub config: AppConfig,
+}
+
+impl Session {
+    pub fn new(
+        app_version: String,
+        mode: &str,
+        port: Option<u16>,
+        workspace_dir: String,
+        config: AppConfig,
+        last_job: Option<JobProgress>,
+    ) -> Self {
+        let saved_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        Session {
+            version: SESSION_VERSION,
+            app_version,
+            saved_at_ms,
+            mode: mode.to_string(),
+            port,
+            workspace_dir,
+            profile: config.profile.clone(),
+            workers: config.workers,
+            last_job,
+            config: redact(config),
+        }
+    }
+}
+
+/// Session files get shared in bug reports, so drop credentials embedded in the proxy URL
+fn redact(mut config: AppConfig) -> AppConfig {
+    if let Some(proxy_url) = &config.proxy_url {
+        if let Ok(mut url) = reqwest::Url::parse(proxy_url) {
+            if url.password().is_some() {
+                let _ = url.set_password(None);
+                config.proxy_url = Some(url.to_string());
+            }
+        }
+    }
+    config
+}
+
+pub fn write(path: &Path, session: &Session) -> Result<(), BackendError> {
+    let contents = serde_json::to_string_pretty(session)
+        .map_err(|e| BackendError::Io(format!("failed to serialize session: {}", e)))?;
+    std::fs::write(path, contents)
+        .map_err(|e| BackendError::Io(format!("failed to write {:?}: {}", path, e)))
+}
+
+/// Read a session file, rejecting versions this build doesn't understand
+pub fn read(path: &Path) -> Result<Session, BackendError> {
+    let contents = std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            BackendError::NotFound(format!("{:?} does not exist", path))
+        }
+        _ => BackendError::Io(format!("failed to read {:?}: {}", path, e)),
+    })?;
+
+    // Check the version first so an incompatible file gets a clear message, not a parse error
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+        BackendError::InvalidArgument(format!("{:?} is not a session file: {}", path, e))
+    })?;
+    match value.get("version").and_then(serde_json::Value::as_u64) {
+        Some(version) if version == u64::from(SESSION_VERSION) => {}
+        Some(version) => {
+            return Err(BackendError::InvalidArgument(format!(
+                "session file version {} is not supported by this app (expected {})",
+                version, SESSION_VERSION
+            )));
+        }
+        None => {
+            return Err(BackendError::InvalidArgument(format!(
+                "{:?} is not a session file (no version)",
+                path
+            )));
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| {
+        BackendError::InvalidArgument(format!("session file {:?} is malformed: {}", path, e))
+    })
+}
@@ -0,0 +1,125 @@
+use log::info;
+use std::sync::Mutex;
+
+/// A backend process `stop_all` can terminate
+pub trait Stoppable: Send {
+    fn pid(&self) -> u32;
+    /// Kill the process and reap it where the platform allows
+    fn stop(self) -> std::io::Result<()>;
+}
+
+/// Outcome of stopping one backend; callers log it
+#[derive(Debug)]
+pub struct Stopped<K> {
+    pub name: K,
+    pub pid: u32,
+    pub result: Result<(), String>,
+}
+
+/// Empty `state` and return what it held. The lock is held only for the swap, so callers
+/// never kill or wait on a process while other commands are blocked on the mutex.
+pub fn take_all<C: Default>(state: &Mutex<C>) -> C {
+    std::mem::take(&mut *state.lock().unwrap())
+}
+
+/// Stop every backend in `backends` at once, one thread each, and return the outcomes in
+/// the order given. Stopping is independent per process, so one slow or stuck kill does not
+/// delay the others; the call returns once all of them are done.
+pub fn stop_all<K, B>(backends: impl IntoIterator<Item = (K, B)>) -> Vec<Stopped<K>>
+where
+    K: std::fmt::Display + Send,
+    B: Stoppable,
+{
+    let backends: Vec<(K, B)> = backends.into_iter().collect();
+    if backends.len() <= 1 {
+        // Nothing to parallelise; skip spawning a thread
+        return backends.into_iter().map(|(name, backend)| stop_one(name, backend)).collect();
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = backends
+            .into_iter()
+            .map(|(name, backend)| scope.spawn(move || stop_one(name, backend)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("backend stop thread panicked"))
+            .collect()
+    })
+}
+
+fn stop_one<K: std::fmt::Display, B: Stoppable>(name: K, backend: B) -> Stopped<K> {
+    let pid = backend.pid();
+    info!("Killing backend {} (PID: {})...", name, pid);
+    let result = backend.stop().map_err(|e| e.to_string());
+    Stopped { name, pid, result }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// Stands in for a backend: a real child process whose stop takes a moment
+    struct MockBackend(std::process::Child);
+
+    impl MockBackend {
+        fn spawn() -> Self {
+            MockBackend(std::process::Command::new("sleep").arg("60").spawn().unwrap())
+        }
+    }
+
+    impl Stoppable for MockBackend {
+        fn pid(&self) -> u32 {
+            self.0.id()
+        }
+
+        fn stop(mut self) -> std::io::Result<()> {
+            std::thread::sleep(Duration::from_millis(300));
+            self.0.kill()?;
+            self.0.wait().map(|_| ())
+        }
+    }
+
+    #[test]
+    fn stops_every_backend_without_holding_the_lock() {
+        let backends = Mutex::new(
+            (0..4)
+                .map(|i| (format!("mock:{}", 50000 + i), MockBackend::spawn()))
+                .collect::<BTreeMap<_, _>>(),
+        );
+        let pids: Vec<u32> = backends.lock().unwrap().values().map(Stoppable::pid).collect();
+
+        let (done, finished) = mpsc::channel();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let stopped = stop_all(take_all(&backends));
+                done.send(stopped).unwrap();
+            });
+
+            // While the stops are still running, the state must be free for other commands
+            std::thread::sleep(Duration::from_millis(100));
+            assert!(backends.try_lock().is_ok_and(|map| map.is_empty()));
+
+            // Four 300 ms stops run in parallel; serially or deadlocked this would time out
+            let stopped = finished.recv_timeout(Duration::from_secs(10)).expect("shutdown deadlocked");
+            assert_eq!(stopped.len(), 4);
+            assert!(stopped.iter().all(|s| s.result.is_ok()), "{:?}", stopped);
+            let names: Vec<&str> = stopped.iter().map(|s| s.name.as_str()).collect();
+            assert_eq!(names, ["mock:50000", "mock:50001", "mock:50002", "mock:50003"]);
+        });
+
+        for pid in pids {
+            assert!(!crate::processes::is_alive(pid), "PID {} still running", pid);
+        }
+    }
+
+    #[test]
+    fn nothing_to_stop() {
+        let backends: Mutex<Option<MockBackend>> = Mutex::new(None);
+        let stopped = stop_all(take_all(&backends).map(|backend| ("mock", backend)));
+        assert!(stopped.is_empty());
+    }
+}
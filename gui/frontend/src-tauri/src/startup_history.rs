@@ -0,0 +1,86 @@
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+const HISTORY_FILE_NAME: &str = "startup_history.json";
+
+/// Startups kept, oldest dropped first
+const MAX_STARTUPS: usize = 50;
+
+/// One backend startup, as reported in `StartupTelemetry`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupRecord {
+    /// Unix time in milliseconds when the backend became ready
+    pub at_ms: u64,
+    pub mode: String,
+    pub port: u16,
+    pub startup_ms: u64,
+}
+
+/// Recent startup durations, persisted next to the config file so a slow-down across
+/// launches (e.g. a growing cache) shows up
+pub struct StartupHistory(Mutex<VecDeque<StartupRecord>>);
+
+fn history_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    use tauri::path::BaseDirectory;
+
+    app_handle
+        .path()
+        .resolve(HISTORY_FILE_NAME, BaseDirectory::AppLocalData)
+        .map_err(|e| format!("Failed to resolve startup history path: {}", e))
+}
+
+impl StartupHistory {
+    /// Load the saved history; a missing or unreadable file starts an empty one
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let records = history_path(app_handle)
+            .and_then(|path| std::fs::read_to_string(&path).map_err(|e| format!("{:?}: {}", path, e)))
+            .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()));
+        let records: VecDeque<StartupRecord> = match records {
+            Ok(records) => records,
+            Err(e) => {
+                debug!("No startup history loaded ({}); starting empty", e);
+                VecDeque::new()
+            }
+        };
+        StartupHistory(Mutex::new(records))
+    }
+
+    /// Append a startup and save the history. Failing to save is logged, never fatal.
+    pub fn record(&self, app_handle: &AppHandle, mode: &str, port: u16, startup_ms: u64) {
+        let at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+        let mut records = self.0.lock().unwrap();
+        if records.len() == MAX_STARTUPS {
+            records.pop_front();
+        }
+        records.push_back(StartupRecord {
+            at_ms,
+            mode: mode.to_string(),
+            port,
+            startup_ms,
+        });
+
+        let saved = history_path(app_handle).and_then(|path| {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("{:?}: {}", parent, e))?;
+            }
+            let contents = serde_json::to_string(&*records).map_err(|e| e.to_string())?;
+            std::fs::write(&path, contents).map_err(|e| format!("{:?}: {}", path, e))
+        });
+        if let Err(e) = saved {
+            warn!("Failed to save startup history: {}", e);
+        }
+    }
+
+    /// Saved startups, oldest first
+    pub fn snapshot(&self) -> Vec<StartupRecord> {
+        self.0.lock().unwrap().iter().cloned().collect()
+    }
+}
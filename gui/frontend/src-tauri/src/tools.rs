@@ -0,0 +1,207 @@
+use log::{debug, info};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+/// CLI tools the webview may run. Read-only validation/analysis tools only: anything
+/// that generates audio or writes files belongs behind a backend endpoint instead.
+pub const ALLOWED_SUBCOMMANDS: [&str; 6] = [
+    "sts-analyze-json",
+    "sts-parse-regression-check-json",
+    "sts-detect-headers",
+    "sts-validate-voice-library-data",
+    "sts-validate-voice-library-config",
+    "sts-validate-voice-library-scripts",
+];
+
+/// Environment variable pointing at a specific `uv` executable
+pub const UV_PATH_ENV_VAR: &str = "STS_UV_PATH";
+
+/// Where uv's installers and package managers put it, searched when `uv` isn't on PATH.
+/// Apps launched from the macOS Finder/Dock get a minimal PATH without the shell's
+/// additions, so a working uv is often just not visible. `~` is the user's home directory.
+const UV_SEARCH_DIRS: &[&str] = &[
+    // Standalone installer (current default, then the pre-0.5 location)
+    "~/.local/bin",
+    "~/.cargo/bin",
+    // Homebrew on Apple silicon, Intel macOS and Linux
+    "/opt/homebrew/bin",
+    "/usr/local/bin",
+    "/home/linuxbrew/.linuxbrew/bin",
+    // pipx, winget and scoop
+    "~/.local/pipx/venvs/uv/bin",
+    "~/AppData/Local/Microsoft/WinGet/Links",
+    "~/scoop/shims",
+];
+
+/// One-off tools should finish quickly; anything slower is treated as hung and killed
+const TOOL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Version probes that take longer than this are abandoned (the tool still counts as found)
+const VERSION_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether an external tool is on PATH, and its version if it reports one
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStatus {
+    pub name: String,
+    pub found: bool,
+    pub path: Option<String>,
+    /// First line of the tool's version output
+    pub version: Option<String>,
+}
+
+/// Captured result of `run_backend_command`
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandOutput {
+    /// `None` if the process was terminated by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Run an allowlisted CLI tool via `uv run` from the project root, capturing its output.
+/// `args[0]` is the tool name; the rest are passed through unchanged (no shell involved).
+pub async fn run(project_root: &str, args: &[String]) -> Result<CommandOutput, String> {
+    let (tool, tool_args) = args
+        .split_first()
+        .ok_or_else(|| "No subcommand given".to_string())?;
+    if !ALLOWED_SUBCOMMANDS.contains(&tool.as_str()) {
+        return Err(format!(
+            "Subcommand {:?} is not allowed (allowed: {})",
+            tool,
+            ALLOWED_SUBCOMMANDS.join(", ")
+        ));
+    }
+
+    info!("Running backend tool: {} {:?}", tool, tool_args);
+    let child = tokio::process::Command::new(resolve_uv())
+        .arg("run")
+        .arg(tool)
+        .args(tool_args)
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Dropping the future on timeout kills the tool rather than leaking it
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", tool, e))?;
+
+    let output = tokio::time::timeout(TOOL_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("{} did not finish within {:?}", tool, TOOL_TIMEOUT))?
+        .map_err(|e| format!("Failed to collect output of {}: {}", tool, e))?;
+
+    Ok(CommandOutput {
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+/// Look up each tool on PATH and ask it for its version, all concurrently
+pub async fn check(names: Vec<String>) -> Vec<ToolStatus> {
+    let probes: Vec<_> = names
+        .into_iter()
+        .map(|name| tauri::async_runtime::spawn(check_one(name)))
+        .collect();
+
+    let mut statuses = Vec::with_capacity(probes.len());
+    for probe in probes {
+        if let Ok(status) = probe.await {
+            statuses.push(status);
+        }
+    }
+    statuses
+}
+
+async fn check_one(name: String) -> ToolStatus {
+    let found = if name == "uv" { find_uv() } else { find_on_path(&name) };
+    let Some(path) = found else {
+        return ToolStatus {
+            name,
+            found: false,
+            path: None,
+            version: None,
+        };
+    };
+
+    let version = probe_version(&path, &name).await;
+    ToolStatus {
+        name,
+        found: true,
+        path: Some(path.to_string_lossy().into_owned()),
+        version,
+    }
+}
+
+/// The `uv` to run: `STS_UV_PATH` > PATH > `UV_SEARCH_DIRS` > plain `uv`, which then
+/// fails to spawn with the usual not-found error
+pub fn resolve_uv() -> PathBuf {
+    find_uv().unwrap_or_else(|| PathBuf::from("uv"))
+}
+
+fn find_uv() -> Option<PathBuf> {
+    if let Some(path) = crate::config::env_var(UV_PATH_ENV_VAR) {
+        return Some(PathBuf::from(path));
+    }
+    if let Some(path) = find_on_path("uv") {
+        return Some(path);
+    }
+
+    let home = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).map(PathBuf::from);
+    let found = UV_SEARCH_DIRS
+        .iter()
+        .filter_map(|dir| match dir.strip_prefix("~/") {
+            Some(relative) => home.as_ref().map(|home| home.join(relative)),
+            None => Some(PathBuf::from(dir)),
+        })
+        .find_map(|dir| find_in(&dir, "uv"));
+    match &found {
+        Some(path) => info!("uv is not on PATH; using {:?}", path),
+        None => debug!("uv not found on PATH or in {:?}", UV_SEARCH_DIRS),
+    }
+    found
+}
+
+/// First `name` executable on PATH
+pub fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| find_in(&dir, name))
+}
+
+fn find_in(dir: &Path, name: &str) -> Option<PathBuf> {
+    let candidates: Vec<String> = if cfg!(windows) {
+        vec![format!("{}.exe", name), format!("{}.cmd", name), name.to_string()]
+    } else {
+        vec![name.to_string()]
+    };
+    candidates
+        .iter()
+        .map(|candidate| dir.join(candidate))
+        .find(|candidate| candidate.is_file())
+}
+
+async fn probe_version(path: &Path, name: &str) -> Option<String> {
+    // ffmpeg and friends use a single-dash flag
+    let flag = if name.starts_with("ff") { "-version" } else { "--version" };
+    let child = tokio::process::Command::new(path)
+        .arg(flag)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .ok()?;
+
+    let output = tokio::time::timeout(VERSION_PROBE_TIMEOUT, child.wait_with_output())
+        .await
+        .ok()?
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+}
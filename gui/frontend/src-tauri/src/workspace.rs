@@ -0,0 +1,585 @@
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::error::BackendError;
+
+/// Env var the backend's settings read the workspace location from (gui_backend/config.py)
+pub const BACKEND_WORKSPACE_ENV_VAR: &str = "WORKSPACE_DIR";
+
+/// Minimum gap between progress reports while copying
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Cache folders the backend creates under `output/<project>/` (see create_output_folders)
+const CACHE_DIR_NAMES: [&str; 2] = ["cache", "dummy_cache"];
+
+/// Scratch folders the backend creates next to an output file while generating
+/// (audio_generation/utils.py); left behind when a job is interrupted
+const TEMP_DIR_NAMES: [&str; 1] = ["temp_batches"];
+
+/// Name prefix of the scratch file `test_write` creates and removes
+const WRITE_TEST_FILE_PREFIX: &str = ".sts-write-test-";
+
+/// Subdirectories the backend expects under the workspace (see gui_backend/config.py)
+pub const WORKSPACE_SUBDIRS: [&str; 5] = [
+    "input",
+    "output",
+    "source_screenplays",
+    "standalone_speech",
+    "uploads",
+];
+
+/// Create the workspace and any missing expected subdirectories, returning the ones created
+pub fn ensure_structure(workspace_dir: &Path) -> Result<Vec<String>, String> {
+    std::fs::create_dir_all(workspace_dir)
+        .map_err(|e| format!("Failed to create workspace {:?}: {}", workspace_dir, e))?;
+
+    let mut created = Vec::new();
+    for name in WORKSPACE_SUBDIRS {
+        let dir = workspace_dir.join(name);
+        if dir.is_dir() {
+            continue;
+        }
+        std::fs::create_dir(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+        created.push(name.to_string());
+    }
+
+    if !created.is_empty() {
+        info!("Created workspace directories in {:?}: {:?}", workspace_dir, created);
+    }
+    Ok(created)
+}
+
+/// Path components that mark a folder kept in sync by a cloud storage client, with the
+/// client's name. Matched by prefix, since clients add suffixes (`OneDrive - Contoso`,
+/// `Dropbox (Personal)`, `GoogleDrive-user@example.com`).
+const SYNC_FOLDER_PREFIXES: [(&str, &str); 6] = [
+    ("Dropbox", "Dropbox"),
+    ("OneDrive", "OneDrive"),
+    ("iCloud Drive", "iCloud Drive"),
+    ("iCloudDrive", "iCloud Drive"),
+    ("Google Drive", "Google Drive"),
+    ("GoogleDrive", "Google Drive"),
+];
+
+/// Where macOS keeps iCloud Drive: `~/Library/Mobile Documents`
+const ICLOUD_CONTAINER_DIR: [&str; 2] = ["Library", "Mobile Documents"];
+
+/// What `get_workspace_info` reports about the workspace location
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceInfo {
+    pub path: String,
+    pub exists: bool,
+    /// The sync client the path appears to belong to, if any
+    pub sync_provider: Option<String>,
+    /// Set with `sync_provider`; shown to the user as-is
+    pub warning: Option<String>,
+}
+
+/// Describe `workspace_dir`, flagging it when it looks like a cloud-synced folder
+pub fn info(workspace_dir: &Path) -> WorkspaceInfo {
+    let sync_provider = sync_provider(workspace_dir);
+    let warning = sync_provider.map(|provider| {
+        warn!("Workspace {:?} appears to be synced by {}", workspace_dir, provider);
+        format!(
+            "The workspace is inside a {} folder. Sync clients can lock or partially upload \
+             files while the backend writes them, which corrupts audio output. \
+             Choose a workspace on a local, unsynced folder.",
+            provider
+        )
+    });
+    WorkspaceInfo {
+        path: workspace_dir.to_string_lossy().into_owned(),
+        exists: workspace_dir.is_dir(),
+        sync_provider: sync_provider.map(str::to_string),
+        warning,
+    }
+}
+
+/// Best-effort guess from the path alone; folders synced some other way are not detected
+fn sync_provider(path: &Path) -> Option<&'static str> {
+    let components: Vec<String> = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+
+    if components
+        .windows(ICLOUD_CONTAINER_DIR.len())
+        .any(|window| window == ICLOUD_CONTAINER_DIR)
+    {
+        return Some("iCloud Drive");
+    }
+    components.iter().find_map(|component| {
+        SYNC_FOLDER_PREFIXES
+            .iter()
+            .find(|(prefix, _)| component.starts_with(prefix))
+            .map(|(_, provider)| *provider)
+    })
+}
+
+/// Resolve `path` (absolute, or relative to the workspace) to an existing file inside
+/// `workspace_dir`. Symlinks are resolved first, so a link can't escape the workspace.
+pub fn resolve_file(workspace_dir: &Path, path: &str) -> Result<PathBuf, BackendError> {
+    let requested = workspace_dir.join(path);
+    let resolved = requested.canonicalize().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => BackendError::NotFound(format!("{:?} does not exist", requested)),
+        _ => BackendError::Io(format!("failed to resolve {:?}: {}", requested, e)),
+    })?;
+    let workspace = workspace_dir
+        .canonicalize()
+        .map_err(|e| BackendError::Io(format!("failed to resolve workspace {:?}: {}", workspace_dir, e)))?;
+
+    if !resolved.starts_with(&workspace) {
+        return Err(BackendError::OutsideWorkspace(format!(
+            "{:?} is not inside the workspace {:?}",
+            resolved, workspace
+        )));
+    }
+    if !resolved.is_file() {
+        return Err(BackendError::InvalidArgument(format!("{:?} is not a file", resolved)));
+    }
+    Ok(resolved)
+}
+
+/// Space on the volume holding the workspace
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskSpace {
+    /// The existing directory that was measured (the workspace or its nearest ancestor)
+    pub path: String,
+    pub mount_point: String,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Free space on the volume containing `path`. If `path` doesn't exist yet,
+/// the nearest existing ancestor is measured instead.
+pub fn free_space(path: &Path) -> Result<DiskSpace, String> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .ok_or_else(|| format!("No existing directory found for {:?}", path))?;
+
+    // The volume is the disk with the longest mount point that contains the path
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| existing.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .ok_or_else(|| format!("Could not determine the volume containing {:?}", existing))?;
+
+    Ok(DiskSpace {
+        path: existing.to_string_lossy().into_owned(),
+        mount_point: disk.mount_point().to_string_lossy().into_owned(),
+        free_bytes: disk.available_space(),
+        total_bytes: disk.total_space(),
+    })
+}
+
+/// Result of a successful `test_write`
+#[derive(Debug, Clone, Serialize)]
+pub struct WriteTest {
+    pub path: String,
+    pub bytes: usize,
+    pub elapsed_ms: u64,
+}
+
+/// Create, read back and delete a scratch file in `workspace_dir`.
+/// Writes are synced to disk so a full volume or a sync client rejecting the file fails here
+/// rather than during a job. The error kind says which step failed and why.
+pub fn test_write(workspace_dir: &Path) -> Result<WriteTest, BackendError> {
+    use std::io::Write;
+
+    let started = Instant::now();
+    if !workspace_dir.is_dir() {
+        return Err(BackendError::NotFound(format!(
+            "workspace {:?} does not exist",
+            workspace_dir
+        )));
+    }
+
+    let path = workspace_dir.join(format!("{}{}", WRITE_TEST_FILE_PREFIX, std::process::id()));
+    let content = format!("script-to-speech write test {:?}\n", std::time::SystemTime::now());
+
+    let written = std::fs::File::create(&path).and_then(|mut file| {
+        file.write_all(content.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&path);
+        return Err(io_error("write", &path, e));
+    }
+
+    let read_back = std::fs::read(&path);
+    // Always try to clean up, but report the first failure
+    let removed = std::fs::remove_file(&path);
+    let read_back = read_back.map_err(|e| io_error("read back", &path, e))?;
+    if read_back != content.as_bytes() {
+        return Err(BackendError::Io(format!(
+            "{:?} read back {} bytes that differ from the {} written",
+            path,
+            read_back.len(),
+            content.len()
+        )));
+    }
+    removed.map_err(|e| io_error("delete", &path, e))?;
+
+    debug!("Workspace write test passed in {:?}", started.elapsed());
+    Ok(WriteTest {
+        path: path.to_string_lossy().into_owned(),
+        bytes: content.len(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Map an I/O failure on `path` to the most specific `BackendError`
+pub fn io_error(action: &str, path: &Path, e: std::io::Error) -> BackendError {
+    use std::io::ErrorKind;
+
+    let message = format!("failed to {} {:?}: {}", action, path, e);
+    match e.kind() {
+        ErrorKind::NotFound => BackendError::NotFound(message),
+        ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => BackendError::PermissionDenied(message),
+        ErrorKind::StorageFull | ErrorKind::QuotaExceeded => BackendError::StorageFull(message),
+        _ => BackendError::Io(message),
+    }
+}
+
+/// Payload of `workspace-migration-progress` and `backend-cache-clear-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct FileProgress {
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    /// Directory currently being processed
+    pub current: String,
+}
+
+/// Check that `to` is a safe target for moving the workspace out of `from`:
+/// absolute, not nested with `from`, and either missing or an empty directory.
+pub fn validate_destination(from: &Path, to: &Path) -> Result<(), BackendError> {
+    if !to.is_absolute() {
+        return Err(BackendError::InvalidArgument(format!(
+            "workspace path {:?} must be absolute",
+            to
+        )));
+    }
+
+    let from = from.canonicalize().unwrap_or_else(|_| from.to_path_buf());
+    let to_resolved = to.canonicalize().unwrap_or_else(|_| to.to_path_buf());
+    if to_resolved.starts_with(&from) || from.starts_with(&to_resolved) {
+        return Err(BackendError::InvalidArgument(format!(
+            "workspace path {:?} must not be inside the current workspace {:?} or contain it",
+            to, from
+        )));
+    }
+
+    if to.exists() {
+        let mut entries = std::fs::read_dir(to).map_err(|e| {
+            BackendError::InvalidArgument(format!("{:?} is not a usable directory: {}", to, e))
+        })?;
+        if entries.next().is_some() {
+            return Err(BackendError::InvalidArgument(format!(
+                "{:?} is not empty; choose an empty or new folder",
+                to
+            )));
+        }
+    }
+    Ok(())
+}
+
+enum Moved {
+    Renamed,
+    Copied,
+}
+
+/// Reports progress, throttled so large copies/deletes don't flood the UI
+struct Progress<F: FnMut(&FileProgress)> {
+    state: FileProgress,
+    report: F,
+    last_report: Instant,
+}
+
+impl<F: FnMut(&FileProgress)> Progress<F> {
+    fn advance(&mut self, bytes: u64) {
+        self.state.bytes_done += bytes;
+        if self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        (self.report)(&self.state);
+        self.last_report = Instant::now();
+    }
+}
+
+/// Move the expected workspace subdirectories from `from` to `to`, returning the ones moved.
+///
+/// Each subdirectory is renamed when possible and copied otherwise (e.g. across drives).
+/// Copied sources are only deleted after everything has arrived; on failure, whatever was
+/// already moved is put back so the original workspace is left intact.
+/// Anything else in `from` is left alone: in production it also holds the app's own config.
+pub fn migrate(
+    from: &Path,
+    to: &Path,
+    report: impl FnMut(&FileProgress),
+) -> Result<Vec<String>, String> {
+    let names: Vec<&str> = WORKSPACE_SUBDIRS
+        .into_iter()
+        .filter(|name| from.join(name).exists())
+        .collect();
+    let sizes: Vec<u64> = names.iter().map(|name| tree_size(&from.join(name))).collect();
+
+    std::fs::create_dir_all(to).map_err(|e| format!("Failed to create {:?}: {}", to, e))?;
+
+    let mut progress = Progress {
+        state: FileProgress {
+            bytes_done: 0,
+            bytes_total: sizes.iter().sum(),
+            current: String::new(),
+        },
+        report,
+        last_report: Instant::now(),
+    };
+    let mut moved: Vec<(&str, Moved)> = Vec::new();
+
+    for (name, size) in names.iter().zip(sizes) {
+        progress.state.current = name.to_string();
+        progress.flush();
+
+        let (src, dst) = (from.join(name), to.join(name));
+        let result = match std::fs::rename(&src, &dst) {
+            Ok(()) => {
+                progress.advance(size);
+                Ok(Moved::Renamed)
+            }
+            Err(e) => {
+                debug!("Rename of {:?} failed ({}), copying instead", src, e);
+                copy_tree(&src, &dst, &mut progress).map(|_| Moved::Copied)
+            }
+        };
+
+        match result {
+            Ok(how) => moved.push((name, how)),
+            Err(e) => {
+                if dst.exists() {
+                    if let Err(e) = std::fs::remove_dir_all(&dst) {
+                        warn!("Failed to remove partial copy {:?}: {}", dst, e);
+                    }
+                }
+                roll_back(from, to, &moved);
+                return Err(format!("Failed to move {:?} to {:?}: {}", src, dst, e));
+            }
+        }
+    }
+    progress.flush();
+
+    for (name, how) in &moved {
+        if let Moved::Copied = how {
+            let src = from.join(name);
+            if let Err(e) = std::fs::remove_dir_all(&src) {
+                warn!("Moved {:?} but failed to delete the original: {}", src, e);
+            }
+        }
+    }
+
+    info!("Moved workspace from {:?} to {:?}: {:?}", from, to, names);
+    Ok(names.into_iter().map(String::from).collect())
+}
+
+/// Delete every project's audio cache folder (`output/<project>/cache` and `dummy_cache`),
+/// returning the bytes freed and the number of folders removed. Nothing outside those
+/// folders is touched, and symlinked folders are skipped rather than followed.
+/// The backend must be stopped first so no in-use file is deleted mid-write.
+pub fn clear_caches(workspace_dir: &Path, report: impl FnMut(&FileProgress)) -> Result<(u64, usize), String> {
+    let cache_dirs = project_dirs(workspace_dir, &CACHE_DIR_NAMES)?;
+
+    let mut progress = Progress {
+        state: FileProgress {
+            bytes_done: 0,
+            bytes_total: cache_dirs.iter().map(|dir| tree_size(dir)).sum(),
+            current: String::new(),
+        },
+        report,
+        last_report: Instant::now(),
+    };
+
+    for dir in &cache_dirs {
+        progress.state.current = dir.to_string_lossy().into_owned();
+        progress.flush();
+        remove_tree(dir, &mut progress).map_err(|e| format!("Failed to clear {:?}: {}", dir, e))?;
+    }
+    progress.flush();
+
+    info!(
+        "Cleared {} cache folder(s), freeing {} bytes",
+        cache_dirs.len(),
+        progress.state.bytes_done
+    );
+    Ok((progress.state.bytes_done, cache_dirs.len()))
+}
+
+/// Backend scratch files found by `temp_usage`
+#[derive(Debug, Clone, Serialize)]
+pub struct TempUsage {
+    pub bytes: u64,
+    /// Scratch folders and leftover write-test files
+    pub paths: Vec<String>,
+}
+
+/// Size of the scratch files interrupted jobs and write tests left in the workspace
+pub fn temp_usage(workspace_dir: &Path) -> Result<TempUsage, String> {
+    let paths = temp_paths(workspace_dir)?;
+    Ok(TempUsage {
+        bytes: paths.iter().map(|path| path_size(path)).sum(),
+        paths: paths
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect(),
+    })
+}
+
+/// Delete what `temp_usage` reports, returning the bytes freed and entries removed.
+/// The backend must be stopped first so no in-use file is deleted mid-write.
+pub fn clear_temp(workspace_dir: &Path) -> Result<(u64, usize), String> {
+    let paths = temp_paths(workspace_dir)?;
+    let mut progress = Progress {
+        state: FileProgress {
+            bytes_done: 0,
+            bytes_total: paths.iter().map(|path| path_size(path)).sum(),
+            current: String::new(),
+        },
+        report: |_: &FileProgress| {},
+        last_report: Instant::now(),
+    };
+
+    for path in &paths {
+        let removed = if path.is_dir() {
+            remove_tree(path, &mut progress)
+        } else {
+            let size = path_size(path);
+            std::fs::remove_file(path).map(|_| progress.advance(size))
+        };
+        removed.map_err(|e| format!("Failed to remove {:?}: {}", path, e))?;
+    }
+
+    info!(
+        "Removed {} backend temp entries, freeing {} bytes",
+        paths.len(),
+        progress.state.bytes_done
+    );
+    Ok((progress.state.bytes_done, paths.len()))
+}
+
+fn temp_paths(workspace_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut paths = project_dirs(workspace_dir, &TEMP_DIR_NAMES)?;
+    if let Ok(entries) = std::fs::read_dir(workspace_dir) {
+        paths.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with(WRITE_TEST_FILE_PREFIX))
+                .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+                .map(|entry| entry.path()),
+        );
+    }
+    Ok(paths)
+}
+
+/// Folders named one of `names` directly inside each `output/<project>/`.
+/// Symlinked folders are skipped so nothing outside the workspace is reached.
+fn project_dirs(workspace_dir: &Path, names: &[&str]) -> Result<Vec<PathBuf>, String> {
+    let output_dir = workspace_dir.join("output");
+    let is_real_dir = |path: &Path| {
+        std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_dir())
+    };
+
+    let mut dirs = Vec::new();
+    if is_real_dir(&output_dir) {
+        let projects = std::fs::read_dir(&output_dir)
+            .map_err(|e| format!("Failed to read {:?}: {}", output_dir, e))?;
+        for project in projects.flatten() {
+            let project = project.path();
+            if !is_real_dir(&project) {
+                continue;
+            }
+            dirs.extend(
+                names
+                    .iter()
+                    .map(|name| project.join(name))
+                    .filter(|dir| is_real_dir(dir)),
+            );
+        }
+    }
+    Ok(dirs)
+}
+
+/// Size of a file, or of everything under a directory
+fn path_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        tree_size(path)
+    } else {
+        std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0)
+    }
+}
+
+/// Delete `dir` file by file so progress can be reported. Symlinks are removed, not followed.
+fn remove_tree<F: FnMut(&FileProgress)>(dir: &Path, progress: &mut Progress<F>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = std::fs::symlink_metadata(entry.path())?;
+        if metadata.file_type().is_dir() {
+            remove_tree(&entry.path(), progress)?;
+        } else {
+            std::fs::remove_file(entry.path())?;
+            progress.advance(metadata.len());
+        }
+    }
+    std::fs::remove_dir(dir)
+}
+
+fn roll_back(from: &Path, to: &Path, moved: &[(&str, Moved)]) {
+    for (name, how) in moved {
+        let (src, dst) = (from.join(name), to.join(name));
+        let result = match how {
+            Moved::Renamed => std::fs::rename(&dst, &src),
+            Moved::Copied => std::fs::remove_dir_all(&dst),
+        };
+        if let Err(e) = result {
+            warn!("Failed to roll back {:?}: {}", dst, e);
+        }
+    }
+}
+
+fn copy_tree<F: FnMut(&FileProgress)>(
+    src: &Path,
+    dst: &Path,
+    progress: &mut Progress<F>,
+) -> std::io::Result<()> {
+    std::fs::create_dir(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_tree(&entry.path(), &target, progress)?;
+        } else {
+            let bytes = std::fs::copy(entry.path(), &target)?;
+            progress.advance(bytes);
+        }
+    }
+    Ok(())
+}
+
+/// Total size of the files under `path`; unreadable entries count as zero
+fn tree_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => tree_size(&entry.path()),
+            _ => entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+        })
+        .sum()
+}
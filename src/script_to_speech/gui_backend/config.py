@@ -85,6 +85,18 @@ class Settings(BaseSettings):
     # but mypy cannot track the transformation, requiring the type: ignore annotation.
     WORKSPACE_DIR: Path = None  # type: ignore[assignment]
 
+    # Where finished audiobooks are written - set via --output-dir (see main.py), which
+    # also exports it as OUTPUT_DIR so reload workers pick it up.
+    # None keeps them in <workspace>/output next to their cache and logs, which always
+    # stay in the workspace.
+    OUTPUT_DIR: Optional[Path] = None
+
+    def audiobook_output_folder(self, workspace_dir: Path, project_name: str) -> Path:
+        """Get the folder a project's finished audiobook is written to."""
+        if self.OUTPUT_DIR is not None:
+            return self.OUTPUT_DIR / project_name
+        return workspace_dir / "output" / project_name
+
     @property
     def AUDIO_OUTPUT_DIR(self) -> Path:
         """Get the standalone speech output directory (workspace-relative)."""
@@ -2,8 +2,10 @@
 
 import argparse
 import asyncio
+import faulthandler
 import multiprocessing
 import os
+import signal
 import sys
 import traceback
 from contextlib import asynccontextmanager
@@ -65,6 +67,14 @@ load_environment_variables()
 if getattr(sys, "frozen", False):
     multiprocessing.freeze_support()
 
+# Dump all thread stacks to stderr on SIGUSR1 so a hung backend can be diagnosed
+# (used by request_backend_dump in the Tauri app). Not available on Windows.
+if hasattr(signal, "SIGUSR1") and sys.stderr is not None:
+    try:
+        faulthandler.register(signal.SIGUSR1, all_threads=True)
+    except (AttributeError, OSError, RuntimeError, ValueError) as e:
+        logger.warning(f"Stack dump on SIGUSR1 unavailable: {e}")
+
 
 # Background task for session cleanup
 async def cleanup_sessions_task() -> None:
@@ -293,10 +303,22 @@ def main() -> None:
         action="store_true",
         help="Disable stdin monitoring (for manual backend testing only - not for Tauri sidecar use)",
     )
+    parser.add_argument(
+        "--output-dir",
+        type=Path,
+        default=None,
+        help="Write finished audiobooks here instead of <workspace>/output (cache and logs stay in the workspace)",
+    )
     # Use parse_known_args() to ignore unknown arguments from Python multiprocessing
     # (e.g., when uvicorn spawns workers with -B -S -I -c ... args)
     args, unknown = parser.parse_known_args()
 
+    if args.output_dir is not None:
+        # Exported so uvicorn's reload worker, which re-creates settings, sees it too
+        settings.OUTPUT_DIR = args.output_dir
+        os.environ["OUTPUT_DIR"] = str(args.output_dir)
+        logger.info(f"Writing finished audiobooks to {args.output_dir}")
+
     # Determine the port: use explicit --port if provided, otherwise use settings default
     port = args.port if args.port else settings.PORT
 
@@ -4,7 +4,7 @@ from typing import List
 
 from fastapi import APIRouter, HTTPException
 
-from ..models import ProviderInfo, ValidationResult
+from ..models import ApiResponse, ProviderInfo, ValidationResult
 from ..services.provider_service import provider_service
 
 router = APIRouter()
@@ -45,6 +45,20 @@ async def get_provider_info(provider: str) -> ProviderInfo:
         )
 
 
+@router.post("/providers/{provider}/test-credentials", response_model=ApiResponse)
+async def test_provider_credentials(provider: str) -> ApiResponse:
+    """Check that a provider's credentials are configured.
+
+    Failures, including an unknown provider, come back as ok=False with the reason
+    in error, so callers can show the message as-is.
+    """
+    try:
+        provider_service.test_credentials(provider)
+    except Exception as e:
+        return ApiResponse(ok=False, error=str(e))
+    return ApiResponse(ok=True, data={"provider": provider})
+
+
 @router.post("/providers/{provider}/validate", response_model=ValidationResult)
 async def validate_provider_config(provider: str, config: dict) -> ValidationResult:
     """Validate a provider configuration."""
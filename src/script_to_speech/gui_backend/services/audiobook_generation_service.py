@@ -1,6 +1,7 @@
 """Audiobook generation service wrapping the core audio generation pipeline."""
 
 import asyncio
+import json
 import logging
 import threading
 import uuid
@@ -81,6 +82,27 @@ PHASE_WEIGHTS = {
     AudiobookGenerationPhase.CANCELLED: (0.0, 0.0),
 }
 
+# Stdout prefix the Tauri shell parses into `backend-job-progress` events; the
+# payload format is defined in gui/frontend/src-tauri/src/progress.rs.
+PROGRESS_MARKER = "STS_PROGRESS "
+PROGRESS_REPORT_INTERVAL_SECONDS = 1.0
+
+
+def _print_progress_marker(progress: AudiobookGenerationProgress) -> None:
+    """Print one progress marker line for the desktop shell to pick up.
+
+    A closed stdout (the shell went away) is not worth failing a generation over.
+    """
+    payload = {
+        "job_id": progress.task_id,
+        "progress": progress.overall_progress,
+        "message": progress.message,
+    }
+    try:
+        print(PROGRESS_MARKER + json.dumps(payload), flush=True)
+    except (OSError, ValueError) as e:
+        logger.debug(f"Could not print progress marker: {e}")
+
 
 class ActiveTaskExistsError(Exception):
     """Raised when a generation is already running for a project.
@@ -586,8 +608,26 @@ class AudiobookGenerationService:
 
         return removed_count
 
+    async def _report_progress(self, task_id: str) -> None:
+        """Print a progress marker whenever a task's progress or message changes.
+
+        Progress is otherwise only polled over HTTP; the markers let the desktop
+        shell follow a generation without polling. Runs until cancelled.
+        """
+        last = None
+        while True:
+            progress = self.get_progress(task_id)
+            if progress is None:
+                return
+            current = (round(progress.overall_progress, 3), progress.message)
+            if current != last:
+                _print_progress_marker(progress)
+                last = current
+            await asyncio.sleep(PROGRESS_REPORT_INTERVAL_SECONDS)
+
     async def _process_task(self, task: AudiobookGenerationTask) -> None:
         """Process a generation task in the background."""
+        reporter = asyncio.create_task(self._report_progress(task.task_id))
         try:
             task.status = TaskStatus.PROCESSING
             task.started_at = datetime.now(timezone.utc)
@@ -630,6 +670,13 @@ class AudiobookGenerationService:
             project_key = Path(task.request.input_json_path).stem
             self.complete_silence_scan(project_key, error=str(e))
 
+        finally:
+            # Always report the terminal state, even if the reporter never saw it
+            reporter.cancel()
+            final = self.get_progress(task.task_id)
+            if final is not None:
+                _print_progress_marker(final)
+
     def _run_generation(self, task: AudiobookGenerationTask) -> None:
         """Run the audiobook generation pipeline (blocking).
 
@@ -680,10 +727,13 @@ class AudiobookGenerationService:
         if log_file:
             setup_screenplay_logging(str(log_file))
 
-        # Build output file path
+        # Build output file path (--output-dir can move it off the workspace volume)
         input_path = Path(request.input_json_path)
         base_name = input_path.stem
-        output_file = main_output_folder / f"{base_name}.mp3"
+        audiobook_folder = settings.audiobook_output_folder(
+            self.workspace_dir, base_name
+        )
+        output_file = audiobook_folder / f"{base_name}.mp3"
 
         # Load voice config
         with open(request.voice_config_path, "r", encoding="utf-8") as f:
@@ -919,6 +969,7 @@ class AudiobookGenerationService:
             concat_tracker = ConcatenationProgressTracker()
             task.set_concat_tracker(concat_tracker)
 
+            audiobook_folder.mkdir(parents=True, exist_ok=True)
             concatenate_tasks_batched(
                 tasks=all_tasks,
                 output_file=str(output_file),
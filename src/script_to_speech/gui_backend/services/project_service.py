@@ -219,7 +219,9 @@ class ProjectService:
             optional_config_file = project_dir / f"{project_name}_optional_config.yaml"
 
             # Check output directory
-            output_project_dir = self.output_dir / project_name
+            output_project_dir = settings.audiobook_output_folder(
+                self.workspace_dir, project_name
+            )
             output_mp3_file = output_project_dir / f"{project_name}.mp3"
 
             has_pdf = pdf_file.exists()
@@ -236,6 +236,22 @@ class ProviderService:
             valid=len(errors) == 0, errors=errors, warnings=warnings
         )
 
+    def test_credentials(self, provider_name: str) -> None:
+        """Check that a provider's credentials are configured.
+
+        Instantiates the provider's client, which fails when its API key is missing.
+        No request is sent to the provider, so a key that is set but revoked still passes.
+
+        Raises:
+            ValueError: If the provider is unknown
+            Exception: Whatever the provider raises when its client can't be created
+                (typically TTSError for a missing API key)
+        """
+        if provider_name not in self._providers_cache:
+            raise ValueError(f"Provider {provider_name} not found")
+
+        get_provider_class(provider_name).instantiate_client()
+
     def _validate_field_value(self, field: ProviderField, value: Any) -> str | None:
         """Validate a field value against its constraints."""
         # Type validation
@@ -342,3 +342,62 @@ class TestProviderConfiguration:
         # Assert
         assert response.status_code == 500
         assert "Failed to validate config" in response.json()["detail"]
+
+
+class TestProviderCredentials:
+    """Tests for the provider credential check endpoint."""
+
+    @pytest.fixture
+    def mock_provider_service(self):
+        """Mock the provider service."""
+        with patch(
+            "script_to_speech.gui_backend.routers.providers.provider_service"
+        ) as mock:
+            yield mock
+
+    def test_test_credentials_success(self, client: TestClient, mock_provider_service):
+        """Test a provider whose client can be created."""
+        # Act
+        response = client.post("/api/providers/openai/test-credentials")
+
+        # Assert
+        assert response.status_code == 200
+        response_data = response.json()
+        assert response_data["ok"] is True
+        assert response_data["data"] == {"provider": "openai"}
+        mock_provider_service.test_credentials.assert_called_once_with("openai")
+
+    def test_test_credentials_missing_key(
+        self, client: TestClient, mock_provider_service
+    ):
+        """Test that a missing API key is reported in the envelope, not as an HTTP error."""
+        # Arrange
+        mock_provider_service.test_credentials.side_effect = Exception(
+            "OPENAI_API_KEY environment variable is not set"
+        )
+
+        # Act
+        response = client.post("/api/providers/openai/test-credentials")
+
+        # Assert
+        assert response.status_code == 200
+        response_data = response.json()
+        assert response_data["ok"] is False
+        assert "OPENAI_API_KEY" in response_data["error"]
+
+    def test_test_credentials_unknown_provider(
+        self, client: TestClient, mock_provider_service
+    ):
+        """Test that an unknown provider is a failed check rather than a 404."""
+        # Arrange
+        mock_provider_service.test_credentials.side_effect = ValueError(
+            "Provider non_existent not found"
+        )
+
+        # Act
+        response = client.post("/api/providers/non_existent/test-credentials")
+
+        # Assert
+        assert response.status_code == 200
+        assert response.json()["ok"] is False
+        assert "not found" in response.json()["error"]
@@ -7,6 +7,7 @@ its cancellation guard and the task-registry behaviour are.
 """
 
 import asyncio
+import json
 from datetime import datetime, timedelta, timezone
 from unittest.mock import MagicMock, patch
 
@@ -18,6 +19,7 @@ from script_to_speech.gui_backend.models import (
     TaskStatus,
 )
 from script_to_speech.gui_backend.services.audiobook_generation_service import (
+    PROGRESS_MARKER,
     ActiveTaskExistsError,
     AudiobookGenerationTask,
     _GenerationCancelled,
@@ -160,3 +162,38 @@ class TestRunGenerationCancellation:
         assert task.phase == AudiobookGenerationPhase.CANCELLED
         assert task.completed_at is not None
         assert task.message == "Generation cancelled"
+
+
+class TestProgressMarkers:
+    def _markers(self, out: str) -> list[dict]:
+        return [
+            json.loads(line[len(PROGRESS_MARKER) :])
+            for line in out.splitlines()
+            if line.startswith(PROGRESS_MARKER)
+        ]
+
+    def test_process_task_prints_the_terminal_state(self, capsys):
+        task = _insert_task("proj", status=TaskStatus.PENDING)
+
+        with patch.object(service, "_run_generation"):
+            asyncio.run(service._process_task(task))
+
+        final = self._markers(capsys.readouterr().out)[-1]
+        assert final == {
+            "job_id": task.task_id,
+            "progress": 1.0,
+            "message": "Audiobook generation completed",
+        }
+
+    def test_cancelled_task_reports_its_message(self, capsys):
+        task = _insert_task("proj", status=TaskStatus.PENDING)
+
+        def raise_cancel(_task):
+            raise _GenerationCancelled()
+
+        with patch.object(service, "_run_generation", side_effect=raise_cancel):
+            asyncio.run(service._process_task(task))
+
+        final = self._markers(capsys.readouterr().out)[-1]
+        assert final["job_id"] == task.task_id
+        assert final["message"] == "Generation cancelled"